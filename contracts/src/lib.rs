@@ -1,7 +1,59 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar::{instructions as instructions_sysvar, slot_hashes};
+use anchor_lang::system_program;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
-declare_id!("TF1111111111111111111111111111111111111111");
+declare_id!("TFRewards1111111111111111111111111111111111");
+
+pub const BYTES_PER_GB: u64 = 1_000_000_000;
+pub const EPOCH_DURATION: i64 = 24 * 60 * 60; // 1 day
+pub const EMISSION_EPOCHS: u64 = 10 * 365;     // 10-year linear release
+pub const MAX_BONUS_TIERS: usize = 4;
+pub const MAX_BONUS_MULTIPLIER: u16 = 500; // 5x
+pub const BPS_DENOMINATOR: u64 = 10_000;
+pub const MAX_SPEED_TIERS: usize = 4;
+pub const SUBSCRIPTION_PERIOD: i64 = 30 * 24 * 60 * 60; // 30 days
+pub const CHANNEL_DISPUTE_PERIOD: i64 = 24 * 60 * 60; // 1 day
+// SuperNode 字段长度上限, 需与结构体上的 #[max_len] 保持一致
+pub const MAX_PEER_ID_LEN: usize = 64;
+pub const MAX_MULTIADDRS: usize = 4;
+pub const MAX_MULTIADDR_LEN: usize = 128;
+pub const MAX_REGION_LEN: usize = 16;
+pub const VOTING_PERIOD: i64 = 3 * 24 * 60 * 60; // 3 days
+pub const PROPOSAL_EXECUTION_WINDOW: i64 = 7 * 24 * 60 * 60; // Executable for 7 days after the timelock
+pub const PROPOSAL_MIN_WEIGHT: u64 = 10_000_000_000_000; // 10,000 TF
+pub const SUPER_NODE_VOTE_MULTIPLIER: u64 = 2;
+pub const VOTE_REWARD_MIN_WEIGHT: u64 = 100_000_000_000; // 100 TF
+pub const REPUTATION_MAX: u32 = 1_000;
+pub const REPUTATION_DECAY_INTERVAL: i64 = 7 * 24 * 60 * 60; // 1 week
+pub const REPUTATION_DECAY_POINTS: u32 = 10;
+pub const REPUTATION_SERVICE_BUMP: i32 = 5;
+pub const REPUTATION_SERVICE_BUMP_INTERVAL: i64 = 24 * 60 * 60; // 1 day
+pub const REPUTATION_CHALLENGE_BUMP: i32 = 10;
+pub const REPUTATION_CHALLENGE_BUMP_INTERVAL: i64 = 24 * 60 * 60; // 1 day
+pub const REPUTATION_CHALLENGE_PENALTY: i32 = 50;
+pub const REPUTATION_SLASH_PENALTY: i32 = 200;
+pub const HEARTBEAT_INTERVAL: i64 = 60 * 60; // 1 hour
+pub const UPTIME_WINDOW_HOURS: u64 = 30 * 24; // Heartbeat history kept on-chain
+pub const MIN_UPTIME_CLAIM_HOURS: u64 = 24;
+// RewardPool.paused 标志位
+pub const PAUSE_GLOBAL: u8 = 1 << 0;
+pub const PAUSE_REWARDS: u8 = 1 << 1;
+pub const PAUSE_STAKING: u8 = 1 << 2;
+pub const PAUSE_CONSUMPTION: u8 = 1 << 3;
+pub const PAUSE_ALL_FLAGS: u8 = PAUSE_GLOBAL | PAUSE_REWARDS | PAUSE_STAKING | PAUSE_CONSUMPTION;
+// 账户布局版本, 结构变化时递增并在迁移指令中处理旧版本
+pub const REWARD_POOL_VERSION: u8 = 1;
+pub const USER_ACCOUNT_VERSION: u8 = 1;
+pub const REWARD_CONFIG_VERSION: u8 = 1;
+pub const PROPOSAL_VERSION: u8 = 1;
+pub const ACCOUNT_RESERVED_BYTES: usize = 64;
+pub const MAX_MULTISIG_SIGNERS: usize = 10; // Must match #[max_len] on Multisig
+pub const POPULARITY_FULL_DOWNLOADS: u64 = 1_000; // Downloads at which popularity reaches 100
 
 #[program]
 pub mod thunderfuel_rewards {
@@ -11,144 +63,659 @@ pub mod thunderfuel_rewards {
     pub fn initialize(ctx: Context<Initialize>, authority: Pubkey) -> Result<()> {
         let reward_pool = &mut ctx.accounts.reward_pool;
         reward_pool.authority = authority;
+        reward_pool.mint = ctx.accounts.mint.key();
+        reward_pool.vault = ctx.accounts.vault.key();
+        reward_pool.bump = *ctx.bumps.get("reward_pool").unwrap();
+        reward_pool.version = REWARD_POOL_VERSION;
         reward_pool.total_rewards = 0;
         reward_pool.total_distributed = 0;
-        reward_pool.upload_reward_rate = 2_000_000_000; // 2 TF per GB (9 decimals)
-        reward_pool.node_reward_rate = 5_000_000_000;   // 5 TF per hour
-        reward_pool.seed_reward_rate = 100_000_000;     // 0.1 TF per hour
+
+        let reward_config = &mut ctx.accounts.reward_config;
+        reward_config.params = RewardParams::default();
+        reward_config.updated_at = Clock::get()?.unix_timestamp;
+        reward_config.version = REWARD_CONFIG_VERSION;
         Ok(())
     }
 
-    /// 奖励用户上传数据
-    pub fn reward_upload(
-        ctx: Context<RewardUpload>,
-        size_gb: u64,
-        rarity_multiplier: u64,
+    /// 更新奖励参数 (仅限奖励池管理员)
+    pub fn update_params(ctx: Context<UpdateParams>, params: RewardParams) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.reward_config.apply(&params, Clock::get()?.unix_timestamp)?;
+
+        emit!(ParamsUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
+        });
+
+        Ok(())
+    }
+
+    /// 设置暂停标志 (管理员可任意设置, 守护者只能增加暂停项)
+    pub fn set_pause(ctx: Context<SetPause>, paused: u8) -> Result<()> {
+        require!(paused & !PAUSE_ALL_FLAGS == 0, ErrorCode::InvalidPauseFlags);
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        let signer = ctx.accounts.authority.key();
+        if signer == reward_pool.authority {
+            authorize(reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+        } else {
+            require!(
+                reward_pool.guardian != Pubkey::default()
+                    && signer == reward_pool.guardian
+                    && ctx.accounts.authority.is_signer,
+                ErrorCode::Unauthorized
+            );
+            require!(
+                paused & reward_pool.paused == reward_pool.paused,
+                ErrorCode::Unauthorized
+            );
+        }
+        reward_pool.paused = paused;
+
+        emit!(PauseUpdatedEvent {
+            paused,
+            updated_by: signer,
+        });
+
+        Ok(())
+    }
+
+    /// 设置守护者密钥 (仅限奖励池管理员), 传入默认公钥表示撤销
+    pub fn set_guardian(ctx: Context<SetGuardian>, guardian: Pubkey) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.reward_pool.guardian = guardian;
+
+        emit!(GuardianUpdatedEvent { guardian });
+
+        Ok(())
+    }
+
+    /// 提议转移管理员 (第一步), 传入默认公钥表示取消
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        ctx.accounts.reward_pool.pending_authority = new_authority;
+
+        emit!(AuthorityProposedEvent {
+            authority: ctx.accounts.reward_pool.authority,
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+
+    /// 新管理员确认接收 (第二步), 多签账户需达到阈值签名
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        require!(
+            reward_pool.pending_authority != Pubkey::default(),
+            ErrorCode::Unauthorized
+        );
+        authorize(reward_pool.pending_authority, &ctx.accounts.new_authority, ctx.remaining_accounts)?;
+
+        let previous = reward_pool.authority;
+        reward_pool.authority = reward_pool.pending_authority;
+        reward_pool.pending_authority = Pubkey::default();
+
+        emit!(AuthorityTransferredEvent {
+            previous,
+            authority: reward_pool.authority,
+        });
+
+        Ok(())
+    }
+
+    /// 创建 M-of-N 多签账户, 可作为奖励池管理员
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.creator = ctx.accounts.creator.key();
+        multisig.set_signers(signers, threshold)?;
+
+        emit!(MultisigUpdatedEvent {
+            multisig: multisig.key(),
+            signers: multisig.signers.clone(),
+            threshold,
+        });
+
+        Ok(())
+    }
+
+    /// 更新多签成员和阈值 (需当前成员达到阈值签名)
+    pub fn update_multisig(
+        ctx: Context<UpdateMultisig>,
+        signers: Vec<Pubkey>,
+        threshold: u8,
     ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.verify(ctx.remaining_accounts)?;
+        multisig.set_signers(signers, threshold)?;
+
+        emit!(MultisigUpdatedEvent {
+            multisig: multisig.key(),
+            signers: multisig.signers.clone(),
+            threshold,
+        });
+
+        Ok(())
+    }
+
+    /// 向奖励池注入 TF 代币, 增加可分发预算
+    pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.total_rewards = reward_pool.total_rewards
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(PoolFundedEvent {
+            funder: ctx.accounts.funder.key(),
+            amount,
+            total_rewards: reward_pool.total_rewards,
+        });
+
+        Ok(())
+    }
+
+    /// 启动挖矿奖励释放 (仅限管理员, 只能调用一次)
+    ///
+    /// `total_emission` 为挖矿奖励总量, 自当前时间起按纪元在十年内线性释放。
+    pub fn start_emission(ctx: Context<StartEmission>, total_emission: u64) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
         let reward_pool = &mut ctx.accounts.reward_pool;
+        require!(reward_pool.emission_start == 0, ErrorCode::EmissionAlreadyStarted);
+        require!(total_emission >= EMISSION_EPOCHS, ErrorCode::InvalidParams);
+
+        reward_pool.emission_start = Clock::get()?.unix_timestamp;
+        reward_pool.emission_total = total_emission;
+
+        emit!(EmissionStartedEvent {
+            start: reward_pool.emission_start,
+            total_emission,
+            epochs: EMISSION_EPOCHS,
+            epoch_duration: EPOCH_DURATION,
+        });
+
+        Ok(())
+    }
+
+    /// 开启当前纪元 (任何人均可调用并支付租金)
+    pub fn open_epoch(ctx: Context<OpenEpoch>, index: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let reward_pool = &ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            reward_pool.current_epoch(now)? == index,
+            ErrorCode::EpochNotActive
+        );
+
+        let reward_epoch = &mut ctx.accounts.reward_epoch;
+        reward_epoch.index = index;
+        reward_epoch.start = reward_pool.epoch_start(index)?;
+        reward_epoch.end = reward_epoch.start
+            .checked_add(EPOCH_DURATION)
+            .ok_or(ErrorCode::MathOverflow)?;
+        reward_epoch.budget = reward_pool.epoch_budget(index);
+        reward_epoch.distributed = 0;
+
+        emit!(EpochOpenedEvent {
+            index,
+            start: reward_epoch.start,
+            end: reward_epoch.end,
+            budget: reward_epoch.budget,
+        });
+
+        Ok(())
+    }
+
+    /// 注册上传回执证明者
+    pub fn register_attestor(ctx: Context<RegisterAttestor>, attestor_key: Pubkey) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let attestor = &mut ctx.accounts.attestor;
+        attestor.key = attestor_key;
+        attestor.registered_at = Clock::get()?.unix_timestamp;
+
+        emit!(AttestorRegisteredEvent {
+            attestor: attestor_key,
+        });
+
+        Ok(())
+    }
+
+    /// 移除上传回执证明者
+    pub fn remove_attestor(ctx: Context<RemoveAttestor>) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        emit!(AttestorRemovedEvent {
+            attestor: ctx.accounts.attestor.key,
+        });
+
+        Ok(())
+    }
+
+    /// 注册超级节点监控者
+    pub fn add_watcher(ctx: Context<AddWatcher>, watcher_key: Pubkey) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        let watcher = &mut ctx.accounts.watcher;
+        watcher.key = watcher_key;
+        watcher.registered_at = Clock::get()?.unix_timestamp;
+
+        emit!(WatcherAddedEvent {
+            watcher: watcher_key,
+        });
+
+        Ok(())
+    }
+
+    /// 移除超级节点监控者
+    pub fn remove_watcher(ctx: Context<RemoveWatcher>) -> Result<()> {
+        authorize(ctx.accounts.reward_pool.authority, &ctx.accounts.authority, ctx.remaining_accounts)?;
+
+        emit!(WatcherRemovedEvent {
+            watcher: ctx.accounts.watcher.key,
+        });
+
+        Ok(())
+    }
+
+    /// 创建用户账户, 可选记录邀请人
+    pub fn register_user(ctx: Context<RegisterUser>, referrer: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+        require!(ctx.accounts.closed_user.data_is_empty(), ErrorCode::AccountClosed);
+
         let user_account = &mut ctx.accounts.user_account;
-        
-        // 计算奖励: size_gb * rate * multiplier
-        let base_reward = size_gb
-            .checked_mul(reward_pool.upload_reward_rate)
+        user_account.owner = ctx.accounts.user.key();
+        user_account.version = USER_ACCOUNT_VERSION;
+
+        if let Some(referrer) = referrer {
+            require_keys_neq!(referrer, user_account.owner, ErrorCode::InvalidReferrer);
+            // 邀请人必须已有用户账户
+            let referrer_account = ctx.accounts.referrer_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidReferrer)?;
+            require_keys_eq!(referrer_account.owner, referrer, ErrorCode::InvalidReferrer);
+            user_account.referrer = referrer;
+        }
+
+        emit!(UserRegisteredEvent {
+            user: user_account.key(),
+            owner: user_account.owner,
+            referrer: user_account.referrer,
+        });
+
+        Ok(())
+    }
+
+    /// 将基线版本的奖励池升级到当前布局 (仅限原管理员)
+    ///
+    /// 基线没有代币金库和参数配置: 本指令创建金库和 RewardConfig, 原有的三项费率写入配置,
+    /// 累计注资和发放统计保留。
+    pub fn migrate_reward_pool(ctx: Context<MigrateRewardPool>) -> Result<()> {
+        let info = ctx.accounts.reward_pool.to_account_info();
+        let new_len = 8 + RewardPool::INIT_SPACE;
+
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == RewardPool::DISCRIMINATOR,
+                ErrorCode::InvalidAccountVersion
+            );
+            require!(data.len() < new_len, ErrorCode::AccountAlreadyMigrated);
+            LegacyRewardPool::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.authority, ctx.accounts.authority.key(), ErrorCode::Unauthorized);
+
+        realloc_account(
+            &info,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+        let reward_pool = legacy.upgrade(
+            ctx.accounts.mint.key(),
+            ctx.accounts.vault.key(),
+            *ctx.bumps.get("reward_pool").unwrap(),
+        );
+        reward_pool.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.reward_config.apply(&legacy.params(), now)?;
+
+        emit!(RewardPoolMigratedEvent {
+            reward_pool: info.key(),
+            version: REWARD_POOL_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// 将基线版本的用户账户扩容并升级到当前版本 (任何人均可代付租金)
+    pub fn migrate_user_account(ctx: Context<MigrateUserAccount>) -> Result<()> {
+        let info = ctx.accounts.user_account.to_account_info();
+        let new_len = 8 + UserAccount::INIT_SPACE;
+
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() >= 8 && data[..8] == UserAccount::DISCRIMINATOR,
+                ErrorCode::InvalidAccountVersion
+            );
+            require!(data.len() < new_len, ErrorCode::AccountAlreadyMigrated);
+            LegacyUserAccount::deserialize(&mut &data[8..])?
+        };
+        require_keys_eq!(legacy.owner, ctx.accounts.owner.key(), ErrorCode::InvalidAccountVersion);
+
+        realloc_account(
+            &info,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            new_len,
+        )?;
+
+        let user_account = legacy.upgrade();
+        user_account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        emit!(UserAccountMigratedEvent {
+            user: info.key(),
+            version: USER_ACCOUNT_VERSION,
+        });
+
+        Ok(())
+    }
+
+    /// 被邀请人达到活跃度门槛后向邀请人发放邀请奖励 (任何人均可调用)
+    pub fn claim_referral_reward(ctx: Context<ClaimReferralReward>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let params = &ctx.accounts.reward_config.params;
+        let invitee_account = &mut ctx.accounts.invitee_account;
+        let referrer_account = &mut ctx.accounts.referrer_account;
+
+        require!(!invitee_account.referral_paid, ErrorCode::ReferralAlreadyPaid);
+        // 被封禁的被邀请人的活跃度不再计入邀请奖励
+        invitee_account.require_not_banned()?;
+        referrer_account.require_not_banned()?;
+
+        require!(invitee_account.referral_active(params), ErrorCode::ReferralNotActive);
+        require!(
+            referrer_account.referrals_paid < params.max_referrals_per_referrer,
+            ErrorCode::ReferralCapReached
+        );
+
+        let reward = params.referral_reward;
+        referrer_account.balance = referrer_account.balance
+            .checked_add(reward)
             .ok_or(ErrorCode::MathOverflow)?;
-        
-        let total_reward = base_reward
-            .checked_mul(rarity_multiplier)
+        referrer_account.referrals_paid = referrer_account.referrals_paid
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        invitee_account.referral_paid = true;
+
+        // 更新奖励池与纪元统计 (超出预算时失败)
+        ctx.accounts.reward_pool.distribute(&mut ctx.accounts.reward_epoch, reward)?;
+
+        emit!(ReferralRewardEvent {
+            referrer: referrer_account.key(),
+            invitee: invitee_account.key(),
+            amount: reward,
+            referrals_paid: referrer_account.referrals_paid,
+        });
+
+        Ok(())
+    }
+
+    /// 奖励用户上传数据
+    ///
+    /// 交易中必须紧接在本指令之前包含一条 ed25519 验签指令，
+    /// 由已注册的证明者对 `receipt` 的 Borsh 序列化结果签名。
+    pub fn reward_upload(ctx: Context<RewardUpload>, receipt: UploadReceipt) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let params = &ctx.accounts.reward_config.params;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.require_not_banned()?;
+
+        // 回执必须属于当前签名用户
+        require_keys_eq!(
+            receipt.user,
+            ctx.accounts.user.key(),
+            ErrorCode::ReceiptUserMismatch
+        );
+        // 防止回执重放: nonce 必须严格递增
+        require!(
+            receipt.nonce > user_account.upload_nonce,
+            ErrorCode::ReceiptNonceUsed
+        );
+
+        // 验证证明者签名
+        verify_ed25519_signature(
+            &ctx.accounts.instructions,
+            &ctx.accounts.attestor.key,
+            &receipt.try_to_vec()?,
+        )?;
+
+        if user_account.owner == Pubkey::default() {
+            // 首次创建: 已关闭过的钱包不能重新创建账户
+            require!(ctx.accounts.closed_user.data_is_empty(), ErrorCode::AccountClosed);
+            user_account.owner = ctx.accounts.user.key();
+            user_account.version = USER_ACCOUNT_VERSION;
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        user_account.decay_reputation(now);
+
+        // 稀缺系数取自内容登记表, 并受信誉上限约束
+        let rarity_multiplier = ctx.accounts.content.rarity_multiplier();
+        let multiplier = (rarity_multiplier * 100).min(user_account.reputation_multiplier_cap());
+
+        // 计算奖励: bytes / 1GB * rate * multiplier%
+        let total_reward = (receipt.bytes as u128)
+            .checked_mul(params.upload_reward_rate as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_mul(multiplier as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BYTES_PER_GB as u128 * 100)
             .ok_or(ErrorCode::MathOverflow)?;
+        let total_reward = u64::try_from(total_reward).map_err(|_| ErrorCode::MathOverflow)?;
 
         // 更新用户余额
         user_account.balance = user_account.balance
             .checked_add(total_reward)
             .ok_or(ErrorCode::MathOverflow)?;
-        
+
         user_account.total_uploaded = user_account.total_uploaded
-            .checked_add(size_gb)
+            .checked_add(receipt.bytes)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        // 更新奖励池统计
-        reward_pool.total_distributed = reward_pool.total_distributed
-            .checked_add(total_reward)
-            .ok_or(ErrorCode::MathOverflow)?;
+        user_account.upload_nonce = receipt.nonce;
+        user_account.bump_service_reputation(now);
+
+        // 更新奖励池与纪元统计 (超出预算时失败)
+        reward_pool.distribute(&mut ctx.accounts.reward_epoch, total_reward)?;
 
         emit!(UploadRewardEvent {
-            user: ctx.accounts.user_account.key(),
+            user: user_account.key(),
+            attestor: ctx.accounts.attestor.key,
             amount: total_reward,
-            size_gb,
+            bytes: receipt.bytes,
+            info_hash: receipt.info_hash,
             rarity_multiplier,
+            nonce: receipt.nonce,
         });
 
         Ok(())
     }
 
-    /// 奖励超级节点运营
-    pub fn reward_super_node(
-        ctx: Context<RewardNode>,
-        duration_hours: u64,
-        uptime_percentage: u8,
-    ) -> Result<()> {
+    /// 超级节点 (或观察者代为) 上报心跳, 每小时记录一次
+    ///
+    /// 暂停期间仍可上报, 否则暂停时段会被计为离线。
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        let owner = ctx.accounts.super_node.owner;
+        let reporter = ctx.accounts.reporter.key();
+        // 节点自身或已登记的观察者才能上报
+        require!(
+            reporter == owner || ctx.accounts.watcher.is_some(),
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.user_account.require_not_banned()?;
+        // 质押低于门槛的节点不再累计在线时长
+        require!(
+            ctx.accounts.user_account.staked_amount >= ctx.accounts.reward_config.params.min_node_stake,
+            ErrorCode::InsufficientStake
+        );
+
+        let hour = (Clock::get()?.unix_timestamp / HEARTBEAT_INTERVAL) as u64;
+        let node_uptime = &mut ctx.accounts.node_uptime;
+        if node_uptime.owner == Pubkey::default() {
+            // 首次上报: 只统计之后的在线时长
+            node_uptime.owner = owner;
+            node_uptime.claimed_until_hour = hour;
+        }
+
+        if node_uptime.record(hour) {
+            emit!(HeartbeatEvent {
+                owner,
+                hour,
+                reporter,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// 奖励超级节点运营, 按链上心跳记录计算领取窗口内的在线率
+    pub fn reward_super_node(ctx: Context<RewardNode>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let params = &ctx.accounts.reward_config.params;
         let reward_pool = &mut ctx.accounts.reward_pool;
         let user_account = &mut ctx.accounts.user_account;
+        let node_uptime = &mut ctx.accounts.node_uptime;
+        user_account.require_not_banned()?;
         
         // 检查是否有足够质押
         require!(
-            user_account.staked_amount >= 10_000_000_000_000, // 10,000 TF
+            user_account.staked_amount >= params.min_node_stake,
             ErrorCode::InsufficientStake
         );
 
-        // 计算基础奖励
-        let base_reward = duration_hours
-            .checked_mul(reward_pool.node_reward_rate)
-            .ok_or(ErrorCode::MathOverflow)?;
+        // 领取窗口: 上次领取之后到当前小时 (不含), 超出历史范围的部分作废
+        let now = Clock::get()?.unix_timestamp;
+        let current_hour = (now / HEARTBEAT_INTERVAL) as u64;
+        let from_hour = node_uptime
+            .claimed_until_hour
+            .max(current_hour.saturating_sub(UPTIME_WINDOW_HOURS));
+        let window_hours = current_hour.saturating_sub(from_hour);
+        require!(window_hours > 0, ErrorCode::ClaimAlreadyProcessed);
+        require!(
+            window_hours >= MIN_UPTIME_CLAIM_HOURS,
+            ErrorCode::UptimeWindowTooShort
+        );
 
-        // 根据在线率调整奖励 (最低90%在线率)
-        require!(uptime_percentage >= 90, ErrorCode::LowUptime);
-        
-        let uptime_bonus = if uptime_percentage >= 99 {
-            120 // 20% bonus for 99%+ uptime
-        } else if uptime_percentage >= 95 {
-            110 // 10% bonus for 95%+ uptime
+        let online_hours = node_uptime.online_hours(from_hour, current_hour);
+        let uptime_percentage = (online_hours * 100 / window_hours) as u8;
+        node_uptime.claimed_until_hour = current_hour;
+
+        // 低于最低在线率时该窗口不发放奖励
+        user_account.decay_reputation(now);
+        let final_reward = if uptime_percentage >= params.min_uptime_percentage {
+            let uptime_bonus = bonus_multiplier(&params.uptime_tiers, uptime_percentage)
+                .min(user_account.reputation_multiplier_cap());
+
+            online_hours
+                .checked_mul(params.node_reward_rate)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_mul(uptime_bonus)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(100)
+                .ok_or(ErrorCode::MathOverflow)?
         } else {
-            100 // no bonus
+            0
         };
 
-        let final_reward = base_reward
-            .checked_mul(uptime_bonus as u64)
-            .ok_or(ErrorCode::MathOverflow)?
-            .checked_div(100)
-            .ok_or(ErrorCode::MathOverflow)?;
+        if final_reward > 0 {
+            // 更新用户余额
+            user_account.balance = user_account.balance
+                .checked_add(final_reward)
+                .ok_or(ErrorCode::MathOverflow)?;
+            user_account.bump_service_reputation(now);
 
-        // 更新用户余额
-        user_account.balance = user_account.balance
-            .checked_add(final_reward)
-            .ok_or(ErrorCode::MathOverflow)?;
+            // 更新奖励池与纪元统计 (超出预算时失败)
+            reward_pool.distribute(&mut ctx.accounts.reward_epoch, final_reward)?;
+        }
 
         user_account.node_operation_hours = user_account.node_operation_hours
-            .checked_add(duration_hours)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        // 更新奖励池统计
-        reward_pool.total_distributed = reward_pool.total_distributed
-            .checked_add(final_reward)
+            .checked_add(online_hours)
             .ok_or(ErrorCode::MathOverflow)?;
 
         emit!(NodeRewardEvent {
-            user: ctx.accounts.user_account.key(),
+            user: user_account.key(),
             amount: final_reward,
-            duration_hours,
+            window_hours,
+            online_hours,
             uptime_percentage,
         });
 
         Ok(())
     }
 
-    /// 奖励长期做种
-    pub fn reward_seeding(
-        ctx: Context<RewardSeed>,
-        duration_hours: u64,
-        file_popularity: u8, // 1-100
-    ) -> Result<()> {
+    /// 奖励长期做种, 从做种记录的领取游标起按小时结算
+    pub fn reward_seeding(ctx: Context<RewardSeed>, duration_hours: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let params = &ctx.accounts.reward_config.params;
         let reward_pool = &mut ctx.accounts.reward_pool;
         let user_account = &mut ctx.accounts.user_account;
+        let seeder = &mut ctx.accounts.seeder;
+        user_account.require_not_banned()?;
+
+        // 每个做种小时只能领取一次: 领取区间不得越过当前时间
+        let now = Clock::get()?.unix_timestamp;
+        let claimed_until = i64::try_from(duration_hours)
+            .ok()
+            .and_then(|hours| hours.checked_mul(HEARTBEAT_INTERVAL))
+            .and_then(|secs| seeder.claimed_until.checked_add(secs))
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            duration_hours > 0 && claimed_until <= now,
+            ErrorCode::ClaimAlreadyProcessed
+        );
+        seeder.claimed_until = claimed_until;
 
         // 计算基础奖励
         let base_reward = duration_hours
-            .checked_mul(reward_pool.seed_reward_rate)
+            .checked_mul(params.seed_reward_rate)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        // 热度系数: 热门文件奖励更高
-        let popularity_multiplier = if file_popularity >= 80 {
-            150 // 50% bonus for very popular files
-        } else if file_popularity >= 50 {
-            125 // 25% bonus for popular files
-        } else if file_popularity <= 20 {
-            200 // 100% bonus for rare files
-        } else {
-            100 // no bonus
-        };
+        // 热度系数: 热门文件和稀有文件奖励更高, 热度取自内容登记表
+        user_account.decay_reputation(now);
+        let file_popularity = ctx.accounts.content.popularity();
+        let popularity_multiplier = bonus_multiplier(&params.popularity_tiers, file_popularity)
+            .min(user_account.reputation_multiplier_cap());
 
         let final_reward = base_reward
-            .checked_mul(popularity_multiplier as u64)
+            .checked_mul(popularity_multiplier)
             .ok_or(ErrorCode::MathOverflow)?
             .checked_div(100)
             .ok_or(ErrorCode::MathOverflow)?;
@@ -161,9 +728,14 @@ pub mod thunderfuel_rewards {
         user_account.seeding_hours = user_account.seeding_hours
             .checked_add(duration_hours)
             .ok_or(ErrorCode::MathOverflow)?;
+        user_account.bump_service_reputation(now);
+
+        // 更新奖励池与纪元统计 (超出预算时失败)
+        reward_pool.distribute(&mut ctx.accounts.reward_epoch, final_reward)?;
 
         emit!(SeedRewardEvent {
-            user: ctx.accounts.user_account.key(),
+            user: user_account.key(),
+            info_hash: ctx.accounts.content.info_hash,
             amount: final_reward,
             duration_hours,
             file_popularity,
@@ -172,294 +744,5566 @@ pub mod thunderfuel_rewards {
         Ok(())
     }
 
-    /// 消耗代币获得下载加速
-    pub fn consume_for_speed(
-        ctx: Context<ConsumeTokens>,
-        amount: u64,
+    /// 登记内容的 Merkle 根 (仅限已注册证明者)
+    pub fn register_content(
+        ctx: Context<RegisterContent>,
+        info_hash: [u8; 32],
+        merkle_root: [u8; 32],
+        piece_count: u32,
+        size_bytes: u64,
     ) -> Result<()> {
-        let user_account = &mut ctx.accounts.user_account;
-        
-        // 检查余额
-        require!(
-            user_account.balance >= amount,
-            ErrorCode::InsufficientBalance
-        );
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
 
-        // 扣除代币
-        user_account.balance = user_account.balance
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
+        require!(piece_count > 0 && size_bytes > 0, ErrorCode::InvalidContent);
 
-        user_account.total_consumed = user_account.total_consumed
-            .checked_add(amount)
+        let content = &mut ctx.accounts.content;
+        content.info_hash = info_hash;
+        content.merkle_root = merkle_root;
+        content.piece_count = piece_count;
+        content.size_bytes = size_bytes;
+        content.registered_by = ctx.accounts.attestor.key;
+        content.registered_at = Clock::get()?.unix_timestamp;
+
+        emit!(ContentRegisteredEvent {
+            info_hash,
+            merkle_root,
+            piece_count,
+            size_bytes,
+            attestor: content.registered_by,
+        });
+
+        Ok(())
+    }
+
+    /// 证明者上报内容的新增下载次数
+    pub fn record_downloads(ctx: Context<RecordDownloads>, count: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let content = &mut ctx.accounts.content;
+        content.download_count = content.download_count
+            .checked_add(count)
             .ok_or(ErrorCode::MathOverflow)?;
 
-        emit!(SpeedBoostEvent {
-            user: ctx.accounts.user_account.key(),
-            amount,
-            new_balance: user_account.balance,
+        emit!(ContentStatsEvent {
+            info_hash: content.info_hash,
+            active_seeders: content.active_seeders,
+            download_count: content.download_count,
         });
 
         Ok(())
     }
 
-    /// 质押代币成为超级节点
-    pub fn stake_for_node(
-        ctx: Context<StakeTokens>,
-        amount: u64,
-    ) -> Result<()> {
-        let user_account = &mut ctx.accounts.user_account;
-        
-        // 检查最小质押量
-        require!(amount >= 10_000_000_000_000, ErrorCode::InsufficientStake);
-        
-        // 检查余额
+    /// 加入内容的做种者集合
+    pub fn start_seeding(ctx: Context<StartSeeding>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        ctx.accounts.user_account.require_not_banned()?;
+
+        let content = &mut ctx.accounts.content;
+        content.active_seeders = content.active_seeders
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        ctx.accounts.user_account.open_seeding()?;
+
+        let seeder = &mut ctx.accounts.seeder;
+        seeder.owner = ctx.accounts.user.key();
+        seeder.info_hash = content.info_hash;
+        seeder.joined_at = Clock::get()?.unix_timestamp;
+        seeder.claimed_until = seeder.joined_at;
+
+        emit!(ContentStatsEvent {
+            info_hash: content.info_hash,
+            active_seeders: content.active_seeders,
+            download_count: content.download_count,
+        });
+
+        Ok(())
+    }
+
+    /// 退出做种者集合并取回租金
+    pub fn stop_seeding(ctx: Context<StopSeeding>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let content = &mut ctx.accounts.content;
+        content.active_seeders = content.active_seeders.saturating_sub(1);
+        ctx.accounts.user_account.close_seeding();
+
+        emit!(ContentStatsEvent {
+            info_hash: content.info_hash,
+            active_seeders: content.active_seeders,
+            download_count: content.download_count,
+        });
+
+        Ok(())
+    }
+
+    /// 对用户发起持有证明挑战, 随机抽取一个分片
+    pub fn issue_challenge(ctx: Context<IssueChallenge>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let content = &ctx.accounts.content;
+        let target_account = &mut ctx.accounts.target_account;
+        require_keys_neq!(
+            target_account.owner,
+            ctx.accounts.challenger.key(),
+            ErrorCode::InvalidChallenge
+        );
+
+        // 挑战者缴纳保证金: 被挑战者按时应答则没收, 超时则退还
+        let bond = ctx.accounts.reward_config.params.challenge_bond;
+        let challenger_account = &mut ctx.accounts.challenger_account;
         require!(
-            user_account.balance >= amount,
+            challenger_account.balance >= bond,
             ErrorCode::InsufficientBalance
         );
+        challenger_account.require_not_banned()?;
+        challenger_account.require_not_frozen()?;
+        challenger_account.balance -= bond;
+        challenger_account.open_escrow()?;
+        target_account.open_escrow()?;
 
-        // 从余额转入质押
-        user_account.balance = user_account.balance
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathOverflow)?;
+        // 以最近的 slot hash 作为随机源, 挑战者无法预先选择分片
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        let recent_hash = slot_hashes
+            .get(16..48)
+            .ok_or(ErrorCode::InvalidChallenge)?;
+        let seed = hashv(&[
+            recent_hash,
+            target_account.key().as_ref(),
+            content.info_hash.as_ref(),
+            ctx.accounts.challenger.key().as_ref(),
+        ]);
+        let mut index_bytes = [0u8; 8];
+        index_bytes.copy_from_slice(&seed.to_bytes()[..8]);
+        let piece_index = (u64::from_le_bytes(index_bytes) % content.piece_count as u64) as u32;
 
-        user_account.staked_amount = user_account.staked_amount
-            .checked_add(amount)
+        let clock = Clock::get()?;
+        let challenge = &mut ctx.accounts.challenge;
+        challenge.challenger = ctx.accounts.challenger.key();
+        challenge.target = target_account.key();
+        challenge.info_hash = content.info_hash;
+        challenge.piece_index = piece_index;
+        challenge.issued_slot = clock.slot;
+        challenge.deadline_slot = clock.slot
+            .checked_add(ctx.accounts.reward_config.params.challenge_response_slots)
             .ok_or(ErrorCode::MathOverflow)?;
+        challenge.bond = bond;
+        challenge.attested = ctx.accounts.attestor.is_some();
 
-        emit!(StakeEvent {
-            user: ctx.accounts.user_account.key(),
-            amount,
-            total_staked: user_account.staked_amount,
+        emit!(ChallengeIssuedEvent {
+            challenger: challenge.challenger,
+            target: challenge.target,
+            info_hash: challenge.info_hash,
+            piece_index,
+            deadline_slot: challenge.deadline_slot,
+            bond,
         });
 
         Ok(())
     }
 
-    /// 解除质押
-    pub fn unstake_tokens(
-        ctx: Context<UnstakeTokens>,
-        amount: u64,
+    /// 被挑战者在截止前提交分片哈希及其 Merkle 路径
+    ///
+    /// 应答截止时间不随暂停顺延, 因此暂停期间仍可应答。
+    pub fn respond_challenge(
+        ctx: Context<RespondChallenge>,
+        piece_hash: [u8; 32],
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
+        let challenge = &ctx.accounts.challenge;
+        require!(
+            Clock::get()?.slot <= challenge.deadline_slot,
+            ErrorCode::ChallengeExpired
+        );
+        require!(
+            verify_merkle_proof(
+                &ctx.accounts.content,
+                challenge.piece_index,
+                piece_hash,
+                &proof,
+            ),
+            ErrorCode::InvalidMerkleProof
+        );
+
+        // 挑战失败, 保证金返还奖励池
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.total_rewards = reward_pool.total_rewards
+            .checked_add(challenge.bond)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        ctx.accounts.challenger_account.release_escrow();
+
         let user_account = &mut ctx.accounts.user_account;
-        
-        // 检查质押余额
+        user_account.release_escrow();
+        user_account.record_challenge_passed(challenge.attested)?;
+        // 只有缴纳了保证金的挑战才加声誉, 且每天至多一次, 防止自我挑战刷分
+        let now = Clock::get()?.unix_timestamp;
+        if challenge.bond > 0
+            && now >= user_account.challenge_bumped_at + REPUTATION_CHALLENGE_BUMP_INTERVAL
+        {
+            user_account.adjust_reputation(REPUTATION_CHALLENGE_BUMP, now);
+            user_account.challenge_bumped_at = now;
+        }
+
+        emit!(ChallengePassedEvent {
+            target: user_account.key(),
+            info_hash: challenge.info_hash,
+            piece_index: challenge.piece_index,
+        });
+
+        Ok(())
+    }
+
+    /// 挑战超时未响应, 记为失败 (任何人均可调用)
+    pub fn expire_challenge(ctx: Context<ExpireChallenge>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        let challenge = &ctx.accounts.challenge;
         require!(
-            user_account.staked_amount >= amount,
-            ErrorCode::InsufficientStake
+            Clock::get()?.slot > challenge.deadline_slot,
+            ErrorCode::ChallengeNotExpired
         );
 
-        // 从质押转回余额
-        user_account.staked_amount = user_account.staked_amount
-            .checked_sub(amount)
+        let challenger_account = &mut ctx.accounts.challenger_account;
+        challenger_account.balance = challenger_account.balance
+            .checked_add(challenge.bond)
             .ok_or(ErrorCode::MathOverflow)?;
+        challenger_account.release_escrow();
 
-        user_account.balance = user_account.balance
-            .checked_add(amount)
+        let target_account = &mut ctx.accounts.target_account;
+        target_account.release_escrow();
+        target_account.challenges_failed = target_account.challenges_failed
+            .checked_add(1)
             .ok_or(ErrorCode::MathOverflow)?;
+        target_account.adjust_reputation(-REPUTATION_CHALLENGE_PENALTY, Clock::get()?.unix_timestamp);
 
-        emit!(UnstakeEvent {
-            user: ctx.accounts.user_account.key(),
-            amount,
-            remaining_staked: user_account.staked_amount,
+        emit!(ChallengeFailedEvent {
+            target: target_account.key(),
+            info_hash: challenge.info_hash,
+            piece_index: challenge.piece_index,
         });
 
         Ok(())
     }
-}
 
-// 数据结构定义
+    /// 消耗代币获得下载加速
+    ///
+    /// 按档位价格购买 `units` 个加速时段, 创建或延长 `SpeedBoost` 凭证供超级节点查询。
+    /// 加速未过期时只能续期同一档位。消耗的代币返还奖励池。
+    pub fn consume_for_speed(
+        ctx: Context<ConsumeTokens>,
+        tier: u8,
+        units: u64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
 
-#[account]
-pub struct RewardPool {
-    pub authority: Pubkey,
-    pub total_rewards: u64,
-    pub total_distributed: u64,
-    pub upload_reward_rate: u64,  // TF per GB
-    pub node_reward_rate: u64,    // TF per hour
-    pub seed_reward_rate: u64,    // TF per hour
-}
+        let speed_tier = ctx.accounts.reward_config.params.speed_tier(tier)?;
+        let amount = speed_tier.price
+            .checked_mul(units)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let extension = speed_tier.duration_slots
+            .checked_mul(units)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(amount > 0, ErrorCode::InvalidSpeedTier);
 
-#[account]
-pub struct UserAccount {
-    pub owner: Pubkey,
-    pub balance: u64,             // Available TF balance
-    pub staked_amount: u64,       // Staked TF for super node
-    pub total_uploaded: u64,      // Total GB uploaded
-    pub total_consumed: u64,      // Total TF consumed for speed
-    pub seeding_hours: u64,       // Total hours seeding
-    pub node_operation_hours: u64, // Total hours operating super node
-    pub reputation_score: u32,    // User reputation (0-1000)
-}
+        let user_account = &mut ctx.accounts.user_account;
+        consume_balance(user_account, &mut ctx.accounts.reward_pool, amount)?;
 
-// Context definitions
+        // 创建或延长加速凭证
+        let current_slot = Clock::get()?.slot;
+        let speed_boost = &mut ctx.accounts.speed_boost;
+        let active = speed_boost.expires_at_slot > current_slot;
+        require!(
+            !active || speed_boost.tier == tier,
+            ErrorCode::SpeedTierMismatch
+        );
+        speed_boost.owner = ctx.accounts.user.key();
+        speed_boost.tier = tier;
+        speed_boost.expires_at_slot = speed_boost.expires_at_slot
+            .max(current_slot)
+            .checked_add(extension)
+            .ok_or(ErrorCode::MathOverflow)?;
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + std::mem::size_of::<RewardPool>(),
-        seeds = [b"reward_pool"],
-        bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        emit!(SpeedBoostEvent {
+            user: user_account.key(),
+            amount,
+            new_balance: user_account.balance,
+            tier,
+            expires_at_slot: speed_boost.expires_at_slot,
+        });
 
-#[derive(Accounts)]
-pub struct RewardUpload<'info> {
-    #[account(
-        mut,
-        seeds = [b"reward_pool"],
-        bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
-    
-    #[account(
-        init_if_needed,
-        payer = user,
-        space = 8 + std::mem::size_of::<UserAccount>(),
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct RewardNode<'info> {
-    #[account(
-        mut,
-        seeds = [b"reward_pool"],
-        bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
-    
-    #[account(
-        mut,
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+    /// 订阅 VIP 加速通道, 立即扣除一个月费用
+    ///
+    /// `auto_renew_allowance` 为预先授权的自动续费总额, 到期后任何人都可以调用
+    /// `renew_subscription` 从中扣费续期。
+    pub fn subscribe(ctx: Context<Subscribe>, auto_renew_allowance: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
 
-#[derive(Accounts)]
-pub struct RewardSeed<'info> {
-    #[account(
-        mut,
-        seeds = [b"reward_pool"],
-        bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
-    
-    #[account(
-        mut,
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+        let price = ctx.accounts.reward_config.params.vip_monthly_price;
+        let now = Clock::get()?.unix_timestamp;
 
-#[derive(Accounts)]
-pub struct ConsumeTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+        let user_account = &mut ctx.accounts.user_account;
+        consume_balance(user_account, &mut ctx.accounts.reward_pool, price)?;
 
-#[derive(Accounts)]
-pub struct StakeTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.owner = ctx.accounts.user.key();
+        subscription.auto_renew_allowance = auto_renew_allowance;
+        subscription.extend(now)?;
 
-#[derive(Accounts)]
-pub struct UnstakeTokens<'info> {
-    #[account(
-        mut,
-        seeds = [b"user", user.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
-    
-    #[account(mut)]
-    pub user: Signer<'info>,
-}
+        emit!(SubscriptionEvent {
+            user: user_account.key(),
+            renewed_by: ctx.accounts.user.key(),
+            amount: price,
+            expires_at: subscription.expires_at,
+            auto_renew_allowance,
+        });
 
-// Event definitions
+        Ok(())
+    }
 
-#[event]
-pub struct UploadRewardEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub size_gb: u64,
-    pub rarity_multiplier: u64,
-}
+    /// 续订 VIP
+    ///
+    /// 订阅者本人可随时续期; 其他调用者只能在到期后, 从预授权额度中扣费续期。
+    pub fn renew_subscription(ctx: Context<RenewSubscription>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
 
-#[event]
-pub struct NodeRewardEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub duration_hours: u64,
-    pub uptime_percentage: u8,
-}
+        let price = ctx.accounts.reward_config.params.vip_monthly_price;
+        let now = Clock::get()?.unix_timestamp;
+        let subscription = &mut ctx.accounts.subscription;
 
-#[event]
-pub struct SeedRewardEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub duration_hours: u64,
-    pub file_popularity: u8,
-}
+        if ctx.accounts.caller.key() != subscription.owner {
+            require!(now >= subscription.expires_at, ErrorCode::SubscriptionNotDue);
+            subscription.auto_renew_allowance = subscription.auto_renew_allowance
+                .checked_sub(price)
+                .ok_or(ErrorCode::AllowanceExhausted)?;
+        }
 
-#[event]
-pub struct SpeedBoostEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub new_balance: u64,
-}
+        let user_account = &mut ctx.accounts.user_account;
+        consume_balance(user_account, &mut ctx.accounts.reward_pool, price)?;
+        subscription.extend(now)?;
 
-#[event]
-pub struct StakeEvent {
-    pub user: Pubkey,
-    pub amount: u64,
-    pub total_staked: u64,
+        emit!(SubscriptionEvent {
+            user: user_account.key(),
+            renewed_by: ctx.accounts.caller.key(),
+            amount: price,
+            expires_at: subscription.expires_at,
+            auto_renew_allowance: subscription.auto_renew_allowance,
+        });
+
+        Ok(())
+    }
+
+    /// 取消自动续费, 已付费的时段在到期前仍然有效
+    pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.auto_renew_allowance = 0;
+
+        emit!(SubscriptionCancelledEvent {
+            user: subscription.owner,
+            expires_at: subscription.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// 登记超级节点的网络端点和能力 (需达到最低质押)
+    pub fn register_super_node(
+        ctx: Context<RegisterSuperNode>,
+        peer_id: String,
+        multiaddrs: Vec<String>,
+        bandwidth_tier: BandwidthTier,
+        region: String,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        ctx.accounts.user_account.require_not_banned()?;
+        require!(
+            ctx.accounts.user_account.staked_amount >= ctx.accounts.reward_config.params.min_node_stake,
+            ErrorCode::InsufficientStake
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let super_node = &mut ctx.accounts.super_node;
+        super_node.owner = ctx.accounts.user.key();
+        super_node.registered_at = now;
+        super_node.set_info(peer_id, multiaddrs, bandwidth_tier, region, now)?;
+
+        emit!(SuperNodeRegisteredEvent {
+            owner: super_node.owner,
+            peer_id: super_node.peer_id.clone(),
+            bandwidth_tier,
+            region: super_node.region.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// 更新超级节点信息 (需仍满足最低质押)
+    pub fn update_super_node(
+        ctx: Context<UpdateSuperNode>,
+        peer_id: String,
+        multiaddrs: Vec<String>,
+        bandwidth_tier: BandwidthTier,
+        region: String,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        ctx.accounts.user_account.require_not_banned()?;
+        require!(
+            ctx.accounts.user_account.staked_amount >= ctx.accounts.reward_config.params.min_node_stake,
+            ErrorCode::InsufficientStake
+        );
+
+        let super_node = &mut ctx.accounts.super_node;
+        super_node.set_info(
+            peer_id,
+            multiaddrs,
+            bandwidth_tier,
+            region,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        emit!(SuperNodeUpdatedEvent {
+            owner: super_node.owner,
+            peer_id: super_node.peer_id.clone(),
+            bandwidth_tier,
+            region: super_node.region.clone(),
+        });
+
+        Ok(())
+    }
+
+    /// 注销超级节点, 租金退还节点所有者
+    ///
+    /// 所有者可随时注销; 质押低于门槛 (或用户账户已关闭) 时任何人均可注销,
+    /// 以清理不再满足条件的节点目录条目。
+    pub fn deregister_super_node(ctx: Context<DeregisterSuperNode>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_REWARDS)?;
+
+        if ctx.accounts.caller.key() != ctx.accounts.owner.key() {
+            let info = ctx.accounts.user_account.to_account_info();
+            let staked_amount = if info.data_is_empty() {
+                0
+            } else {
+                UserAccount::try_deserialize(&mut &info.try_borrow_data()?[..])?.staked_amount
+            };
+            require!(
+                staked_amount < ctx.accounts.reward_config.params.min_node_stake,
+                ErrorCode::Unauthorized
+            );
+        }
+
+        emit!(SuperNodeDeregisteredEvent {
+            owner: ctx.accounts.super_node.owner,
+            caller: ctx.accounts.caller.key(),
+        });
+
+        Ok(())
+    }
+
+    /// 下载者发布带宽需求, 按最高单价托管 TF
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        auction_id: u64,
+        info_hash: [u8; 32],
+        size_gb: u64,
+        max_price_per_gb: u64,
+        bidding_period: i64,
+        delivery_period: i64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        require!(
+            size_gb > 0 && max_price_per_gb > 0 && bidding_period > 0 && delivery_period > 0,
+            ErrorCode::InvalidAuction
+        );
+        let escrow = size_gb
+            .checked_mul(max_price_per_gb)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let downloader_account = &mut ctx.accounts.downloader_account;
+        require!(
+            downloader_account.balance >= escrow,
+            ErrorCode::InsufficientBalance
+        );
+        // 托管金最终转给中标节点, 投票期间同样锁定
+        downloader_account.require_unlocked(now)?;
+        downloader_account.require_not_banned()?;
+        downloader_account.require_not_frozen()?;
+        downloader_account.balance -= escrow;
+        downloader_account.open_escrow()?;
+
+        let auction = &mut ctx.accounts.auction;
+        auction.downloader = ctx.accounts.downloader.key();
+        auction.auction_id = auction_id;
+        auction.info_hash = info_hash;
+        auction.size_gb = size_gb;
+        auction.max_price_per_gb = max_price_per_gb;
+        auction.escrow = escrow;
+        auction.bid_deadline = now
+            .checked_add(bidding_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+        auction.delivery_period = delivery_period;
+        auction.delivery_deadline = 0;
+        auction.winner = Pubkey::default();
+        auction.winning_price_per_gb = 0;
+        auction.awarded = false;
+
+        emit!(AuctionCreatedEvent {
+            auction: auction.key(),
+            downloader: auction.downloader,
+            info_hash,
+            size_gb,
+            max_price_per_gb,
+            bid_deadline: auction.bid_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// 超级节点报价 (TF/GB), 只接受低于当前最低价的报价
+    pub fn submit_bid(ctx: Context<SubmitBid>, price_per_gb: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let auction = &mut ctx.accounts.auction;
+        let node = ctx.accounts.node.key();
+
+        require!(
+            Clock::get()?.unix_timestamp < auction.bid_deadline,
+            ErrorCode::BiddingClosed
+        );
+        require_keys_neq!(node, auction.downloader, ErrorCode::InvalidBid);
+        ctx.accounts.node_account.require_not_banned()?;
+        require!(
+            ctx.accounts.node_account.staked_amount >= ctx.accounts.reward_config.params.min_node_stake,
+            ErrorCode::InsufficientStake
+        );
+        require!(
+            price_per_gb > 0 && price_per_gb <= auction.max_price_per_gb,
+            ErrorCode::InvalidBid
+        );
+        require!(
+            auction.winner == Pubkey::default() || price_per_gb < auction.winning_price_per_gb,
+            ErrorCode::BidNotLowest
+        );
+
+        // 当前最低价节点结算时需要其账户: 被压价的前一节点释放, 新节点登记
+        if auction.winner != node {
+            if auction.winner != Pubkey::default() {
+                ctx.accounts.previous_winner_account
+                    .as_mut()
+                    .ok_or(ErrorCode::InvalidBid)?
+                    .release_escrow();
+            }
+            ctx.accounts.node_account.open_escrow()?;
+        }
+
+        auction.winner = node;
+        auction.winning_price_per_gb = price_per_gb;
+
+        emit!(BidSubmittedEvent {
+            auction: auction.key(),
+            node,
+            price_per_gb,
+        });
+
+        Ok(())
+    }
+
+    /// 竞价结束后确定中标节点 (任何人均可调用)
+    ///
+    /// 无人报价时全额退款并关闭拍卖; 否则按中标价保留托管, 差额退还下载者。
+    pub fn close_bidding(ctx: Context<CloseBidding>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let auction = &mut ctx.accounts.auction;
+        let downloader_account = &mut ctx.accounts.downloader_account;
+
+        require!(!auction.awarded, ErrorCode::InvalidAuction);
+        require!(now >= auction.bid_deadline, ErrorCode::BiddingOpen);
+
+        if auction.winner == Pubkey::default() {
+            downloader_account.balance = downloader_account.balance
+                .checked_add(auction.escrow)
+                .ok_or(ErrorCode::MathOverflow)?;
+            downloader_account.release_escrow();
+
+            emit!(AuctionSettledEvent {
+                auction: auction.key(),
+                winner: Pubkey::default(),
+                paid: 0,
+                refunded: auction.escrow,
+            });
+
+            return auction.close(ctx.accounts.downloader.to_account_info());
+        }
+
+        let cost = auction.size_gb
+            .checked_mul(auction.winning_price_per_gb)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let refund = auction.escrow - cost;
+        downloader_account.balance = downloader_account.balance
+            .checked_add(refund)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        auction.escrow = cost;
+        auction.awarded = true;
+        auction.delivery_deadline = now
+            .checked_add(auction.delivery_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(AuctionAwardedEvent {
+            auction: auction.key(),
+            winner: auction.winner,
+            price_per_gb: auction.winning_price_per_gb,
+            escrow: cost,
+            delivery_deadline: auction.delivery_deadline,
+        });
+
+        Ok(())
+    }
+
+    /// 下载者确认交付, 托管款支付给中标节点
+    ///
+    /// 交付截止时间不随暂停顺延, 因此暂停期间仍可确认 (只记入余额, 提现仍受暂停限制)。
+    pub fn confirm_delivery(ctx: Context<ConfirmDelivery>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        auction.pay_winner(
+            auction.key(),
+            &mut ctx.accounts.node_account,
+            &mut ctx.accounts.downloader_account,
+        )
+    }
+
+    /// 中标节点凭证明者签发的交付回执领取托管款, 无需下载者配合
+    ///
+    /// 交易中必须紧接在本指令之前包含一条 ed25519 验签指令，
+    /// 由已注册的证明者对 `receipt` 的 Borsh 序列化结果签名。
+    /// 与 confirm_delivery 相同, 暂停期间仍可领取。
+    pub fn claim_delivery(ctx: Context<ClaimDelivery>, receipt: DeliveryReceipt) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        require!(
+            receipt.auction == auction.key()
+                && receipt.winner == auction.winner
+                && receipt.info_hash == auction.info_hash
+                && receipt.size_gb == auction.size_gb,
+            ErrorCode::InvalidDeliveryReceipt
+        );
+        verify_ed25519_signature(
+            &ctx.accounts.instructions,
+            &ctx.accounts.attestor.key,
+            &receipt.try_to_vec()?,
+        )?;
+
+        auction.pay_winner(
+            auction.key(),
+            &mut ctx.accounts.node_account,
+            &mut ctx.accounts.downloader_account,
+        )
+    }
+
+    /// 交付超时, 托管款退还下载者 (任何人均可调用)
+    pub fn refund_auction(ctx: Context<RefundAuction>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let auction = &ctx.accounts.auction;
+        require!(auction.awarded, ErrorCode::InvalidAuction);
+        require!(
+            Clock::get()?.unix_timestamp >= auction.delivery_deadline,
+            ErrorCode::DeliveryNotExpired
+        );
+
+        let downloader_account = &mut ctx.accounts.downloader_account;
+        downloader_account.balance = downloader_account.balance
+            .checked_add(auction.escrow)
+            .ok_or(ErrorCode::MathOverflow)?;
+        downloader_account.release_escrow();
+        ctx.accounts.node_account.release_escrow();
+
+        emit!(AuctionSettledEvent {
+            auction: auction.key(),
+            winner: auction.winner,
+            paid: 0,
+            refunded: auction.escrow,
+        });
+
+        Ok(())
+    }
+
+    /// 下载者向超级节点开启单向支付通道, 从余额中锁定押金
+    ///
+    /// 同一对付款方/收款方可开启多个通道, 以 `channel_id` 区分;
+    /// 凭证绑定通道编号和开启时间, 旧通道的凭证不能用于新通道。
+    pub fn open_channel(ctx: Context<OpenChannel>, channel_id: u64, deposit: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        require!(deposit > 0, ErrorCode::InvalidChannel);
+
+        let payer_account = &mut ctx.accounts.payer_account;
+        require!(
+            payer_account.balance >= deposit,
+            ErrorCode::InsufficientBalance
+        );
+        // 押金最终转给收款方, 投票期间同样锁定
+        payer_account.require_unlocked(Clock::get()?.unix_timestamp)?;
+        payer_account.require_not_banned()?;
+        payer_account.require_not_frozen()?;
+        ctx.accounts.payee_account.require_not_banned()?;
+        payer_account.balance -= deposit;
+        payer_account.open_escrow()?;
+        ctx.accounts.payee_account.open_escrow()?;
+
+        let channel = &mut ctx.accounts.channel;
+        channel.payer = ctx.accounts.payer.key();
+        channel.payee = ctx.accounts.payee_account.owner;
+        channel.channel_id = channel_id;
+        channel.deposit = deposit;
+        channel.claimed_amount = 0;
+        channel.closing = false;
+        channel.dispute_ends_at = 0;
+        channel.opened_at = Clock::get()?.unix_timestamp;
+
+        emit!(ChannelOpenedEvent {
+            channel: channel.key(),
+            payer: channel.payer,
+            payee: channel.payee,
+            channel_id,
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    /// 发起关闭通道并进入争议期
+    ///
+    /// 收款方提交付款方签名的最高累计凭证 (需在前一条 ed25519 指令中验签);
+    /// 付款方只能以 0 金额发起关闭, 由收款方在争议期内提交凭证。
+    pub fn close_channel(ctx: Context<UpdateChannel>, amount: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let channel = &mut ctx.accounts.channel;
+        let signer = ctx.accounts.signer.key();
+        require!(!channel.closing, ErrorCode::ChannelClosing);
+
+        if signer == channel.payee {
+            channel.verify_voucher(channel.key(), &ctx.accounts.instructions, amount)?;
+        } else {
+            require_keys_eq!(signer, channel.payer, ErrorCode::Unauthorized);
+            require!(amount == 0, ErrorCode::InvalidVoucher);
+        }
+
+        channel.claimed_amount = amount;
+        channel.closing = true;
+        channel.dispute_ends_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(CHANNEL_DISPUTE_PERIOD)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(ChannelClosingEvent {
+            channel: channel.key(),
+            closed_by: signer,
+            claimed_amount: amount,
+            dispute_ends_at: channel.dispute_ends_at,
+        });
+
+        Ok(())
+    }
+
+    /// 争议期内收款方提交更高的累计凭证
+    ///
+    /// 争议期不随暂停顺延, 因此暂停期间仍可提交。
+    pub fn dispute(ctx: Context<UpdateChannel>, amount: u64) -> Result<()> {
+        let channel = &mut ctx.accounts.channel;
+        require!(channel.closing, ErrorCode::ChannelNotClosing);
+        require!(
+            Clock::get()?.unix_timestamp < channel.dispute_ends_at,
+            ErrorCode::DisputePeriodEnded
+        );
+        require_keys_eq!(
+            ctx.accounts.signer.key(),
+            channel.payee,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > channel.claimed_amount, ErrorCode::InvalidVoucher);
+        channel.verify_voucher(channel.key(), &ctx.accounts.instructions, amount)?;
+
+        channel.claimed_amount = amount;
+
+        emit!(ChannelDisputedEvent {
+            channel: channel.key(),
+            claimed_amount: amount,
+        });
+
+        Ok(())
+    }
+
+    /// 争议期结束后结算通道 (任何人均可调用)
+    pub fn settle_channel(ctx: Context<SettleChannel>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_CONSUMPTION)?;
+
+        let channel = &ctx.accounts.channel;
+        require!(channel.closing, ErrorCode::ChannelNotClosing);
+        require!(
+            Clock::get()?.unix_timestamp >= channel.dispute_ends_at,
+            ErrorCode::DisputePeriodActive
+        );
+
+        let paid = channel.claimed_amount;
+        let refunded = channel.deposit - paid;
+
+        let payee_account = &mut ctx.accounts.payee_account;
+        payee_account.balance = payee_account.balance
+            .checked_add(paid)
+            .ok_or(ErrorCode::MathOverflow)?;
+        payee_account.release_escrow();
+
+        let payer_account = &mut ctx.accounts.payer_account;
+        payer_account.balance = payer_account.balance
+            .checked_add(refunded)
+            .ok_or(ErrorCode::MathOverflow)?;
+        payer_account.release_escrow();
+
+        emit!(ChannelSettledEvent {
+            channel: channel.key(),
+            paid,
+            refunded,
+        });
+
+        Ok(())
+    }
+
+    /// 质押代币成为超级节点
+    pub fn stake_for_node(
+        ctx: Context<StakeTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.require_not_banned()?;
+        
+        // 检查最小质押量
+        require!(
+            amount >= ctx.accounts.reward_config.params.min_node_stake,
+            ErrorCode::InsufficientStake
+        );
+        
+        // 检查余额
+        require!(
+            user_account.balance >= amount,
+            ErrorCode::InsufficientBalance
+        );
+
+        // 从余额转入质押
+        user_account.balance = user_account.balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        user_account.staked_amount = user_account.staked_amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(StakeEvent {
+            user: user_account.key(),
+            amount,
+            total_staked: user_account.staked_amount,
+        });
+
+        Ok(())
+    }
+
+    /// 解除质押
+    ///
+    /// 质押先转入解锁队列, 冷却期结束后通过 `complete_unstake` 回到余额。
+    /// 再次解除质押会累加到同一队列并重新计算冷却期。
+    pub fn unstake_tokens(
+        ctx: Context<UnstakeTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let unbonding = &mut ctx.accounts.unbonding;
+        user_account.require_not_frozen()?;
+        
+        // 检查质押余额
+        require!(
+            user_account.staked_amount >= amount,
+            ErrorCode::InsufficientStake
+        );
+
+        // 解锁期间被罚没的队列不能继续累加
+        require!(
+            unbonding.amount == 0 || unbonding.slash_count == user_account.slash_count,
+            ErrorCode::SlashedDuringUnbonding
+        );
+
+        // 从质押转入解锁队列
+        user_account.staked_amount = user_account.staked_amount
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        unbonding.owner = ctx.accounts.user.key();
+        unbonding.amount = unbonding.amount
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        unbonding.unlock_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(ctx.accounts.reward_config.params.unbonding_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+        unbonding.slash_count = user_account.slash_count;
+
+        emit!(UnstakeEvent {
+            user: user_account.key(),
+            amount,
+            remaining_staked: user_account.staked_amount,
+            pending_amount: unbonding.amount,
+            unlock_at: unbonding.unlock_at,
+        });
+
+        Ok(())
+    }
+
+    /// 冷却期结束后将解锁队列转回余额
+    pub fn complete_unstake(ctx: Context<CloseUnbonding>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let unbonding = &ctx.accounts.unbonding;
+
+        require!(
+            Clock::get()?.unix_timestamp >= unbonding.unlock_at,
+            ErrorCode::UnbondingNotComplete
+        );
+        require!(
+            unbonding.slash_count == user_account.slash_count,
+            ErrorCode::SlashedDuringUnbonding
+        );
+
+        user_account.balance = user_account.balance
+            .checked_add(unbonding.amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(UnstakeCompletedEvent {
+            user: user_account.key(),
+            amount: unbonding.amount,
+            new_balance: user_account.balance,
+        });
+
+        Ok(())
+    }
+
+    /// 取消解锁, 将队列中的代币重新质押
+    pub fn cancel_unstake(ctx: Context<CloseUnbonding>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let unbonding = &ctx.accounts.unbonding;
+        // 重新质押属于新的承诺
+        user_account.require_not_banned()?;
+
+        user_account.staked_amount = user_account.staked_amount
+            .checked_add(unbonding.amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(UnstakeCancelledEvent {
+            user: user_account.key(),
+            amount: unbonding.amount,
+            total_staked: user_account.staked_amount,
+        });
+
+        Ok(())
+    }
+
+    /// 已注册监控者提交或附议罚没证据, 同一证据获得 slash_watcher_quorum 个监控者附议后执行罚没
+    ///
+    /// 按比例扣除质押 (含解锁队列中的部分), 首个提交证据的监控者获得固定奖励, 其余返还奖励池。
+    /// 每个监控者对同一证据只能附议一次, 同一证据只能执行一次。
+    pub fn slash_node(ctx: Context<SlashNode>, evidence_hash: [u8; 32]) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let params = &ctx.accounts.reward_config.params;
+        let node_account = &mut ctx.accounts.node_account;
+        let watcher = ctx.accounts.reporter.key();
+
+        require_keys_neq!(node_account.owner, watcher, ErrorCode::Unauthorized);
+
+        let slash_record = &mut ctx.accounts.slash_record;
+        require!(slash_record.slashed_at == 0, ErrorCode::SlashExecuted);
+        if slash_record.approvals == 0 {
+            // 首个监控者登记证据
+            slash_record.node = node_account.key();
+            slash_record.reporter = watcher;
+            slash_record.evidence_hash = evidence_hash;
+        }
+        slash_record.approvals = slash_record.approvals
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let slash_vote = &mut ctx.accounts.slash_vote;
+        slash_vote.record = slash_record.key();
+        slash_vote.watcher = watcher;
+        slash_vote.voted_at = Clock::get()?.unix_timestamp;
+
+        emit!(SlashApprovedEvent {
+            node: node_account.key(),
+            watcher,
+            evidence_hash,
+            approvals: slash_record.approvals,
+        });
+
+        if slash_record.approvals < params.slash_watcher_quorum {
+            return Ok(());
+        }
+
+        // 奖励归首个提交证据的监控者
+        let reporter_account = &mut ctx.accounts.reporter_account;
+        require_keys_eq!(reporter_account.owner, slash_record.reporter, ErrorCode::Unauthorized);
+
+        // 罚没基数包含解锁中的质押, 防止节点先解除质押再作恶
+        let unbonding_amount = ctx.accounts.node_unbonding
+            .as_ref()
+            .map_or(0, |unbonding| unbonding.amount);
+        let slashable = node_account.staked_amount
+            .checked_add(unbonding_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let slash_amount = (slashable as u128)
+            .checked_mul(params.slash_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(BPS_DENOMINATOR as u128)
+            .ok_or(ErrorCode::MathOverflow)? as u64;
+        require!(slash_amount > 0, ErrorCode::NothingToSlash);
+
+        // 优先扣除质押, 不足部分从解锁队列扣除
+        let from_stake = slash_amount.min(node_account.staked_amount);
+        node_account.staked_amount -= from_stake;
+        if let Some(unbonding) = ctx.accounts.node_unbonding.as_mut() {
+            unbonding.amount = unbonding.amount
+                .checked_sub(slash_amount - from_stake)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        node_account.slash_count = node_account.slash_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        node_account.adjust_reputation(-REPUTATION_SLASH_PENALTY, Clock::get()?.unix_timestamp);
+
+        // 举报者奖励, 其余返还奖励池
+        let reporter_reward = params.slash_reporter_reward.min(slash_amount);
+        reporter_account.balance = reporter_account.balance
+            .checked_add(reporter_reward)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        reward_pool.total_rewards = reward_pool.total_rewards
+            .checked_add(slash_amount - reporter_reward)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        slash_record.amount = slash_amount;
+        slash_record.slashed_at = Clock::get()?.unix_timestamp;
+
+        emit!(SlashEvent {
+            node: node_account.key(),
+            reporter: slash_record.reporter,
+            evidence_hash,
+            amount: slash_amount,
+            reporter_reward,
+            remaining_staked: node_account.staked_amount,
+        });
+
+        Ok(())
+    }
+
+    /// 举报虚假上传或垃圾灌水, 举报者需从余额中缴纳保证金
+    ///
+    /// 裁决前被举报账户的所有流出均被冻结; 超过 `fraud_report_period` 仍未裁决的举报
+    /// 可由任何人调用 expire_fraud_report 驳回。
+    pub fn report_fraud(
+        ctx: Context<ReportFraud>,
+        kind: FraudKind,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let target_account = &mut ctx.accounts.target_account;
+        let reporter_account = &mut ctx.accounts.reporter_account;
+        require_keys_neq!(
+            target_account.owner,
+            ctx.accounts.reporter.key(),
+            ErrorCode::Unauthorized
+        );
+        reporter_account.require_not_banned()?;
+        reporter_account.require_not_frozen()?;
+        let params = &ctx.accounts.reward_config.params;
+        let bond = params.fraud_report_bond;
+        require!(
+            reporter_account.balance >= bond,
+            ErrorCode::InsufficientBalance
+        );
+        reporter_account.open_report(params.max_open_reports)?;
+        reporter_account.balance -= bond;
+        reporter_account.open_escrow()?;
+        target_account.open_escrow()?;
+        // 裁决前冻结被举报账户的流出
+        target_account.pending_reports = target_account.pending_reports
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let fraud_report = &mut ctx.accounts.fraud_report;
+        fraud_report.reporter = ctx.accounts.reporter.key();
+        fraud_report.target = target_account.key();
+        fraud_report.kind = kind;
+        fraud_report.evidence_hash = evidence_hash;
+        fraud_report.bond = bond;
+        fraud_report.status = FraudStatus::Pending;
+        fraud_report.uphold_votes = 0;
+        fraud_report.reject_votes = 0;
+        fraud_report.reported_at = Clock::get()?.unix_timestamp;
+        fraud_report.expires_at = fraud_report.reported_at
+            .checked_add(params.fraud_report_period)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(FraudReportedEvent {
+            report: fraud_report.key(),
+            reporter: fraud_report.reporter,
+            target: fraud_report.target,
+            kind,
+            evidence_hash,
+            expires_at: fraud_report.expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// 管理员/多签直接裁决举报
+    ///
+    /// 成立时按白皮书罚则结算 (金额见奖励参数, 默认值如下): 虚假上传扣除 100 TF,
+    /// 其中 50% 奖励举报者; 垃圾灌水永久封禁并没收全部 TF, 举报者获得 100 TF。
+    /// 不成立时保证金归入奖励池。
+    pub fn adjudicate_fraud(ctx: Context<AdjudicateFraud>, upheld: bool) -> Result<()> {
+        let resolution = &mut ctx.accounts.resolution;
+        resolution.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let adjudicator = &ctx.accounts.adjudicator;
+        authorize(resolution.reward_pool.authority, adjudicator, ctx.remaining_accounts)?;
+        require_keys_neq!(
+            adjudicator.key(),
+            resolution.fraud_report.reporter,
+            ErrorCode::Unauthorized
+        );
+
+        resolution.resolve(upheld, adjudicator.key())
+    }
+
+    /// 已注册证明者对举报投票, 任一方向达到 fraud_attestor_quorum 票即按该方向裁决
+    ///
+    /// 每个证明者对同一举报只能投一票, 举报者和被举报者不能参与。
+    pub fn vote_fraud_report(ctx: Context<VoteFraudReport>, upheld: bool) -> Result<()> {
+        let resolution = &mut ctx.accounts.resolution;
+        resolution.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let voter = ctx.accounts.voter.key();
+        let fraud_report = &mut resolution.fraud_report;
+        require!(
+            fraud_report.status == FraudStatus::Pending,
+            ErrorCode::FraudReportResolved
+        );
+        require_keys_neq!(voter, fraud_report.reporter, ErrorCode::Unauthorized);
+        require_keys_neq!(voter, resolution.target_account.owner, ErrorCode::Unauthorized);
+
+        let tally = if upheld {
+            &mut fraud_report.uphold_votes
+        } else {
+            &mut fraud_report.reject_votes
+        };
+        *tally = tally.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        let votes = *tally;
+
+        let fraud_vote = &mut ctx.accounts.fraud_vote;
+        fraud_vote.report = fraud_report.key();
+        fraud_vote.attestor = voter;
+        fraud_vote.upheld = upheld;
+        fraud_vote.voted_at = Clock::get()?.unix_timestamp;
+
+        emit!(FraudVoteEvent {
+            report: fraud_report.key(),
+            attestor: voter,
+            upheld,
+            uphold_votes: fraud_report.uphold_votes,
+            reject_votes: fraud_report.reject_votes,
+        });
+
+        if votes >= resolution.reward_config.params.fraud_attestor_quorum {
+            resolution.resolve(upheld, voter)?;
+        }
+
+        Ok(())
+    }
+
+    /// 举报超过期限仍未裁决, 按不成立处理: 保证金归入奖励池并解除冻结 (任何人均可调用)
+    pub fn expire_fraud_report(ctx: Context<ExpireFraudReport>) -> Result<()> {
+        let resolution = &mut ctx.accounts.resolution;
+        resolution.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        require!(
+            Clock::get()?.unix_timestamp >= resolution.fraud_report.expires_at,
+            ErrorCode::FraudReportNotExpired
+        );
+
+        resolution.resolve(false, Pubkey::default())
+    }
+
+    /// 存入 TF 代币到程序金库
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        if user_account.owner == Pubkey::default() {
+            // 首次创建: 已关闭过的钱包不能重新创建账户
+            require!(ctx.accounts.closed_user.data_is_empty(), ErrorCode::AccountClosed);
+            user_account.owner = ctx.accounts.user.key();
+            user_account.version = USER_ACCOUNT_VERSION;
+        }
+        user_account.require_not_banned()?;
+
+        user_account.balance = user_account.balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(DepositEvent {
+            user: user_account.key(),
+            amount,
+            new_balance: user_account.balance,
+        });
+
+        Ok(())
+    }
+
+    /// 从程序金库提取 TF 代币
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &mut ctx.accounts.user_account;
+
+        // 检查余额
+        require!(
+            user_account.balance >= amount,
+            ErrorCode::InsufficientBalance
+        );
+
+        // 投票期间锁定, 防止转移代币后重复投票
+        user_account.require_unlocked(Clock::get()?.unix_timestamp)?;
+        user_account.require_not_frozen()?;
+
+        user_account.balance = user_account.balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let signer_seeds: &[&[&[u8]]] = &[&[b"reward_pool", &[ctx.accounts.reward_pool.bump]]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+
+        emit!(WithdrawEvent {
+            user: user_account.key(),
+            amount,
+            new_balance: user_account.balance,
+        });
+
+        Ok(())
+    }
+
+    /// 关闭用户账户: 提取剩余余额并将租金退还给用户
+    ///
+    /// 要求无质押、无进行中的解绑、无未结的通道/拍卖/挑战/举报、无未退出的做种记录、
+    /// 未被封禁且不在治理锁定期内。
+    /// 关闭是永久的: 同时创建 `ClosedUser` 记录, 该钱包不能再创建用户账户,
+    /// 以免重置回执 nonce 和邀请奖励状态。
+    pub fn close_user_account(ctx: Context<CloseUserAccount>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_STAKING)?;
+
+        let user_account = &ctx.accounts.user_account;
+        // 被封禁账户不能关闭后重新注册
+        user_account.require_not_banned()?;
+        require!(
+            user_account.staked_amount == 0 && ctx.accounts.unbonding.data_is_empty(),
+            ErrorCode::StakeOutstanding
+        );
+        // 通道、拍卖、挑战和举报结算时都需要本账户
+        require!(user_account.open_escrows == 0, ErrorCode::OpenEscrows);
+        // 未退出的做种记录会一直计入 content.active_seeders
+        require!(user_account.active_seedings == 0, ErrorCode::ActiveSeedings);
+        user_account.require_unlocked(Clock::get()?.unix_timestamp)?;
+        user_account.require_not_frozen()?;
+
+        let amount = user_account.balance;
+        if amount > 0 {
+            let signer_seeds: &[&[&[u8]]] = &[&[b"reward_pool", &[ctx.accounts.reward_pool.bump]]];
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.user_token_account.to_account_info(),
+                        authority: ctx.accounts.reward_pool.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
+        ctx.accounts.user_account.balance = 0;
+
+        let closed_user = &mut ctx.accounts.closed_user;
+        closed_user.owner = ctx.accounts.user.key();
+        closed_user.closed_at = Clock::get()?.unix_timestamp;
+
+        emit!(UserAccountClosedEvent {
+            user: ctx.accounts.user_account.key(),
+            withdrawn: amount,
+        });
+
+        Ok(())
+    }
+
+    /// 创建治理提案
+    ///
+    /// 经济参数提案需附带新的奖励参数, 协议升级提案仅在链上记录表决结果。
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        kind: ProposalKind,
+        params: Option<RewardParams>,
+        description_hash: [u8; 32],
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        let proposer_account = &mut ctx.accounts.proposer_account;
+        let min_node_stake = ctx.accounts.reward_config.params.min_node_stake;
+        require!(
+            proposer_account.voting_weight(min_node_stake)? >= PROPOSAL_MIN_WEIGHT,
+            ErrorCode::InsufficientVotingWeight
+        );
+
+        // 提案保证金: 通过则退还, 未通过则归入奖励池
+        let bond = ctx.accounts.reward_config.params.proposal_bond;
+        require!(proposer_account.balance >= bond, ErrorCode::InsufficientBalance);
+        proposer_account.require_not_banned()?;
+        proposer_account.require_not_frozen()?;
+        proposer_account.balance -= bond;
+        proposer_account.open_escrow()?;
+
+        match kind {
+            ProposalKind::EconomicParams => {
+                params.as_ref().ok_or(ErrorCode::InvalidProposal)?.validate()?;
+            }
+            ProposalKind::ProtocolUpgrade => {
+                require!(params.is_none(), ErrorCode::InvalidProposal);
+            }
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let reward_pool = &mut ctx.accounts.reward_pool;
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = reward_pool.proposal_count;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.kind = kind;
+        proposal.params = params;
+        proposal.description_hash = description_hash;
+        proposal.created_at = now;
+        proposal.voting_ends_at = now
+            .checked_add(VOTING_PERIOD)
+            .ok_or(ErrorCode::MathOverflow)?;
+        proposal.executable_at = proposal.voting_ends_at
+            .checked_add(kind.timelock())
+            .ok_or(ErrorCode::MathOverflow)?;
+        proposal.expires_at = proposal.executable_at
+            .checked_add(PROPOSAL_EXECUTION_WINDOW)
+            .ok_or(ErrorCode::MathOverflow)?;
+        proposal.config_updated_at = ctx.accounts.reward_config.updated_at;
+        proposal.yes_weight = 0;
+        proposal.no_weight = 0;
+        proposal.executed = false;
+        proposal.quorum = ctx.accounts.reward_config.params.proposal_quorum;
+        proposal.bond = bond;
+        proposal.version = PROPOSAL_VERSION;
+
+        reward_pool.proposal_count = reward_pool.proposal_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(ProposalCreatedEvent {
+            proposal: proposal.key(),
+            id: proposal.id,
+            proposer: proposal.proposer,
+            kind,
+            voting_ends_at: proposal.voting_ends_at,
+            executable_at: proposal.executable_at,
+            expires_at: proposal.expires_at,
+            quorum: proposal.quorum,
+        });
+
+        Ok(())
+    }
+
+    /// 对提案投票, 权重为余额加质押, 超级节点加倍
+    pub fn cast_vote(ctx: Context<CastVote>, approve: bool) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        let voter_account = &mut ctx.accounts.voter_account;
+        voter_account.require_not_banned()?;
+
+        require!(
+            Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+            ErrorCode::VotingClosed
+        );
+
+        let weight = voter_account.voting_weight(ctx.accounts.reward_config.params.min_node_stake)?;
+        require!(weight > 0, ErrorCode::InsufficientVotingWeight);
+
+        proposal.record_vote(approve, weight)?;
+        voter_account.governance_locked_until = voter_account
+            .governance_locked_until
+            .max(proposal.voting_ends_at);
+
+        // 投票奖励: 预算耗尽、无当前纪元或本纪元已达领取次数上限时不发放, 但不影响投票
+        let params = &ctx.accounts.reward_config.params;
+        let rewarded = weight >= VOTE_REWARD_MIN_WEIGHT
+            && ctx.accounts.reward_epoch.as_ref().is_some_and(|epoch| {
+                voter_account.take_vote_reward(epoch.index, params.max_vote_rewards_per_epoch)
+            });
+        let reward = if rewarded { params.vote_reward } else { 0 };
+        let reward = ctx.accounts.reward_pool
+            .distribute_available(ctx.accounts.reward_epoch.as_deref_mut(), reward)?;
+        voter_account.balance = voter_account.balance
+            .checked_add(reward)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.voter = voter_account.key();
+        vote_record.approve = approve;
+        vote_record.weight = weight;
+        vote_record.delegate = Pubkey::default();
+        vote_record.reward = reward;
+        vote_record.shared = 0;
+
+        emit!(VoteCastEvent {
+            proposal: proposal.key(),
+            voter: voter_account.key(),
+            delegate: Pubkey::default(),
+            approve,
+            weight,
+            reward,
+        });
+
+        Ok(())
+    }
+
+    /// 将投票权委托给另一个用户账户
+    pub fn delegate_votes(ctx: Context<DelegateVotes>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        ctx.accounts.delegator_account.require_not_banned()?;
+        ctx.accounts.delegate_account.require_not_banned()?;
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.delegator = ctx.accounts.delegator_account.key();
+        delegation.delegate = ctx.accounts.delegate_account.key();
+        delegation.created_at = Clock::get()?.unix_timestamp;
+
+        emit!(DelegationEvent {
+            delegator: delegation.delegator,
+            delegate: delegation.delegate,
+            active: true,
+        });
+
+        Ok(())
+    }
+
+    /// 撤销投票委托, 已经计入的投票不受影响
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        emit!(DelegationEvent {
+            delegator: ctx.accounts.delegation.delegator,
+            delegate: ctx.accounts.delegation.delegate,
+            active: false,
+        });
+
+        Ok(())
+    }
+
+    /// 按受托人的投票方向计入委托人的权重 (任何人均可调用)
+    ///
+    /// 委托人已亲自投票时无法重复计入。委托人从受托人本次的投票奖励中
+    /// 分得 delegator_share_bps, 所有委托人的分成合计不超过该奖励。
+    pub fn cast_delegated_vote(ctx: Context<CastDelegatedVote>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        let params = &ctx.accounts.reward_config.params;
+        let proposal = &mut ctx.accounts.proposal;
+        let delegator_account = &mut ctx.accounts.delegator_account;
+        let delegate_vote = &mut ctx.accounts.delegate_vote_record;
+        delegator_account.require_not_banned()?;
+
+        require!(
+            Clock::get()?.unix_timestamp < proposal.voting_ends_at,
+            ErrorCode::VotingClosed
+        );
+
+        let weight = delegator_account.voting_weight(params.min_node_stake)?;
+        require!(weight > 0, ErrorCode::InsufficientVotingWeight);
+
+        proposal.record_vote(delegate_vote.approve, weight)?;
+        delegator_account.governance_locked_until = delegator_account
+            .governance_locked_until
+            .max(proposal.voting_ends_at);
+
+        // 委托收益从受托人的投票奖励中转出, 不由奖励池另行发放。
+        // 受托人余额不足时按余额发放, 不影响投票
+        let delegate_account = &mut ctx.accounts.delegate_account;
+        let reward = if weight >= VOTE_REWARD_MIN_WEIGHT {
+            delegate_vote
+                .take_delegator_share(params.delegator_share_bps)
+                .min(delegate_account.balance)
+        } else {
+            0
+        };
+        delegate_account.balance -= reward;
+        delegator_account.balance = delegator_account.balance
+            .checked_add(reward)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.voter = delegator_account.key();
+        vote_record.approve = delegate_vote.approve;
+        vote_record.weight = weight;
+        vote_record.delegate = delegate_vote.voter;
+        vote_record.reward = reward;
+        vote_record.shared = 0;
+
+        emit!(VoteCastEvent {
+            proposal: proposal.key(),
+            voter: delegator_account.key(),
+            delegate: delegate_vote.voter,
+            approve: delegate_vote.approve,
+            weight,
+            reward,
+        });
+
+        Ok(())
+    }
+
+    /// 时间锁结束后执行已通过的提案 (任何人均可调用)
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        let now = Clock::get()?.unix_timestamp;
+        proposal.check_executable(now, ctx.accounts.reward_config.updated_at)?;
+
+        if let Some(params) = proposal.params.as_ref() {
+            ctx.accounts.reward_config.apply(params, now)?;
+            emit!(ParamsUpdated {
+                authority: proposal.key(),
+                params: params.clone(),
+            });
+        }
+        proposal.executed = true;
+
+        emit!(ProposalExecutedEvent {
+            proposal: proposal.key(),
+            id: proposal.id,
+            kind: proposal.kind,
+        });
+
+        Ok(())
+    }
+
+    /// 投票结束后结算提案保证金 (任何人均可调用): 通过则退还提案人, 否则归入奖励池
+    pub fn settle_proposal_bond(ctx: Context<SettleProposalBond>) -> Result<()> {
+        ctx.accounts.reward_pool.require_not_paused(PAUSE_GLOBAL)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        require!(
+            Clock::get()?.unix_timestamp >= proposal.voting_ends_at,
+            ErrorCode::VotingNotEnded
+        );
+        require!(!proposal.bond_settled, ErrorCode::ProposalBondSettled);
+
+        let bond = proposal.bond;
+        let refunded = proposal.passed();
+        let proposer_account = &mut ctx.accounts.proposer_account;
+        if refunded {
+            proposer_account.balance = proposer_account.balance
+                .checked_add(bond)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            let reward_pool = &mut ctx.accounts.reward_pool;
+            reward_pool.total_rewards = reward_pool.total_rewards
+                .checked_add(bond)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        proposer_account.release_escrow();
+        proposal.bond_settled = true;
+
+        emit!(ProposalBondSettledEvent {
+            proposal: proposal.key(),
+            proposer: proposal.proposer,
+            bond,
+            refunded,
+        });
+
+        Ok(())
+    }
+}
+
+// 辅助函数
+
+/// 校验 `authority` 账户即为 `expected`, 且满足签名要求:
+/// 普通密钥需直接签名; 多签账户需在 `signers` 中提供达到阈值的成员签名。
+fn authorize(expected: Pubkey, authority: &AccountInfo, signers: &[AccountInfo]) -> Result<()> {
+    require_keys_eq!(authority.key(), expected, ErrorCode::Unauthorized);
+    if authority.is_signer {
+        return Ok(());
+    }
+
+    require_keys_eq!(*authority.owner, crate::ID, ErrorCode::Unauthorized);
+    let multisig = Multisig::try_deserialize(&mut &authority.try_borrow_data()?[..])?;
+    multisig.verify(signers)
+}
+
+/// 由 `payer` 补足免租金额后把账户扩容到 `new_len`, 新增部分填零
+fn realloc_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let shortfall = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+    info.realloc(new_len, true)?;
+    Ok(())
+}
+
+/// 返回 `value` 命中的第一个加成档位的百分比系数, 未命中时为 100 (无加成)
+fn bonus_multiplier(tiers: &[BonusTier], value: u8) -> u64 {
+    tiers
+        .iter()
+        .find(|tier| tier.multiplier > 0 && (tier.min..=tier.max).contains(&value))
+        .map_or(100, |tier| tier.multiplier as u64)
+}
+
+/// 校验前一条指令是否为 ed25519 程序对 `message` 的单签名验证,
+/// 且签名公钥为 `signer`。签名、公钥和消息都必须内联在该指令数据中。
+fn verify_ed25519_signature(
+    instructions: &AccountInfo,
+    signer: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current_index = instructions_sysvar::load_current_index_checked(instructions)?;
+    require!(current_index > 0, ErrorCode::MissingSignatureInstruction);
+
+    let ix = instructions_sysvar::load_instruction_at_checked(
+        (current_index - 1) as usize,
+        instructions,
+    )?;
+    check_ed25519_instruction(&ix, signer, message)
+}
+
+/// 校验单条 ed25519 程序指令只验证了一个内联签名, 且其公钥和消息与预期一致。
+/// 签名本身由 ed25519 程序在交易执行时校验。
+fn check_ed25519_instruction(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::MissingSignatureInstruction
+    );
+
+    // 布局: [签名数量, 填充, 7 x u16 偏移量, ...数据]
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ErrorCode::InvalidSignatureInstruction
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let signature_ix = read_u16(4);
+    let pubkey_offset = read_u16(6) as usize;
+    let pubkey_ix = read_u16(8);
+    let message_offset = read_u16(10) as usize;
+    let message_size = read_u16(12) as usize;
+    let message_ix = read_u16(14);
+    require!(
+        signature_ix == u16::MAX && pubkey_ix == u16::MAX && message_ix == u16::MAX,
+        ErrorCode::InvalidSignatureInstruction
+    );
+
+    let signed_pubkey = data
+        .get(pubkey_offset..pubkey_offset + 32)
+        .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+    let signed_message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidSignatureInstruction)?;
+    require!(
+        signed_pubkey == signer.as_ref() && signed_message == message,
+        ErrorCode::InvalidSignatureInstruction
+    );
+
+    Ok(())
+}
+
+/// 校验分片哈希到内容 Merkle 根的路径。
+/// 叶子为分片哈希, 父节点为 sha256(左 || 右), 叶子数不足 2 的幂时以全零哈希补齐,
+/// 因此路径长度必须等于树高。
+fn verify_merkle_proof(
+    content: &Content,
+    piece_index: u32,
+    piece_hash: [u8; 32],
+    proof: &[[u8; 32]],
+) -> bool {
+    let depth = content.piece_count.next_power_of_two().trailing_zeros() as usize;
+    if proof.len() != depth {
+        return false;
+    }
+
+    let mut node = piece_hash;
+    let mut index = piece_index;
+    for sibling in proof {
+        node = if index & 1 == 0 {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        };
+        index >>= 1;
+    }
+    node == content.merkle_root
+}
+
+/// 扣除用户余额用于网络内消费, 消耗的代币回流奖励池
+fn consume_balance(
+    user_account: &mut UserAccount,
+    reward_pool: &mut RewardPool,
+    amount: u64,
+) -> Result<()> {
+    user_account.require_not_banned()?;
+    user_account.require_not_frozen()?;
+    // 检查余额
+    require!(
+        user_account.balance >= amount,
+        ErrorCode::InsufficientBalance
+    );
+
+    // 扣除代币
+    user_account.balance = user_account.balance
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    user_account.total_consumed = user_account.total_consumed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    reward_pool.total_rewards = reward_pool.total_rewards
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    Ok(())
+}
+
+// 数据结构定义
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardPool {
+    pub authority: Pubkey,
+    pub mint: Pubkey,             // TF token mint
+    pub vault: Pubkey,            // Program-owned token account backing all balances
+    pub bump: u8,
+    pub total_rewards: u64,       // Total TF funded via fund_pool
+    pub total_distributed: u64,   // Total TF paid out as rewards
+    pub emission_start: i64,      // Unix timestamp emission began, 0 if not started
+    pub emission_total: u64,      // Mining rewards released over EMISSION_EPOCHS
+    pub proposal_count: u64,      // Next governance proposal id
+    pub guardian: Pubkey,         // Key allowed to pause, default if unset
+    pub paused: u8,               // PAUSE_* flags
+    pub pending_authority: Pubkey, // Proposed authority awaiting acceptance
+    pub version: u8,              // REWARD_POOL_VERSION at last write
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES], // Zeroed space for future fields
+}
+
+impl RewardPool {
+    /// 全局或对应指令族被暂停时拒绝执行
+    ///
+    /// 有截止时间的防御性操作 (应答挑战、通道争议、确认交付) 和心跳不检查暂停,
+    /// 避免暂停期间截止时间照常流逝而导致用户受罚。
+    pub fn require_not_paused(&self, family: u8) -> Result<()> {
+        require!(
+            self.paused & (PAUSE_GLOBAL | family) == 0,
+            ErrorCode::ProgramPaused
+        );
+        Ok(())
+    }
+
+    /// 尚未分发的已注资奖励
+    pub fn available_rewards(&self) -> u64 {
+        self.total_rewards.saturating_sub(self.total_distributed)
+    }
+
+    /// 记录一笔奖励分发, 同时计入当前纪元预算。
+    /// 奖励池注资不足时返回 `RewardPoolExhausted`, 纪元预算不足时返回 `EpochBudgetExhausted`
+    pub fn distribute(&mut self, epoch: &mut RewardEpoch, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            now >= epoch.start && now < epoch.end,
+            ErrorCode::EpochNotActive
+        );
+        require!(
+            amount <= epoch.budget.saturating_sub(epoch.distributed),
+            ErrorCode::EpochBudgetExhausted
+        );
+        require!(
+            amount <= self.available_rewards(),
+            ErrorCode::RewardPoolExhausted
+        );
+
+        epoch.distributed = epoch.distributed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_distributed = self.total_distributed
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 尽力发放奖励: 纪元未开启或预算不足时按剩余额度发放 (可能为 0) 而不报错,
+    /// 返回实际发放数量。用于不应因奖励耗尽而失败的操作 (如投票)。
+    pub fn distribute_available(
+        &mut self,
+        epoch: Option<&mut RewardEpoch>,
+        amount: u64,
+    ) -> Result<u64> {
+        let Some(epoch) = epoch else {
+            return Ok(0);
+        };
+        let now = Clock::get()?.unix_timestamp;
+        if now < epoch.start || now >= epoch.end {
+            return Ok(0);
+        }
+        let amount = amount
+            .min(epoch.budget.saturating_sub(epoch.distributed))
+            .min(self.available_rewards());
+        self.distribute(epoch, amount)?;
+        Ok(amount)
+    }
+
+    /// `now` 所在的纪元序号
+    pub fn current_epoch(&self, now: i64) -> Result<u64> {
+        require!(
+            self.emission_start > 0 && now >= self.emission_start,
+            ErrorCode::EmissionNotStarted
+        );
+        let index = ((now - self.emission_start) / EPOCH_DURATION) as u64;
+        require!(index < EMISSION_EPOCHS, ErrorCode::EmissionEnded);
+        Ok(index)
+    }
+
+    pub fn epoch_start(&self, index: u64) -> Result<i64> {
+        (index as i64)
+            .checked_mul(EPOCH_DURATION)
+            .and_then(|offset| self.emission_start.checked_add(offset))
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))
+    }
+
+    /// 线性释放: 每个纪元预算相同, 除不尽的余数计入最后一个纪元
+    pub fn epoch_budget(&self, index: u64) -> u64 {
+        let budget = self.emission_total / EMISSION_EPOCHS;
+        if index == EMISSION_EPOCHS - 1 {
+            budget + self.emission_total % EMISSION_EPOCHS
+        } else {
+            budget
+        }
+    }
+}
+
+/// 已部署的基线版本 RewardPool 布局 (无金库和版本字段), 仅用于迁移
+#[derive(AnchorDeserialize)]
+pub struct LegacyRewardPool {
+    pub authority: Pubkey,
+    pub total_rewards: u64,
+    pub total_distributed: u64,
+    pub upload_reward_rate: u64,
+    pub node_reward_rate: u64,
+    pub seed_reward_rate: u64,
+}
+
+impl LegacyRewardPool {
+    pub fn upgrade(&self, mint: Pubkey, vault: Pubkey, bump: u8) -> RewardPool {
+        RewardPool {
+            authority: self.authority,
+            mint,
+            vault,
+            bump,
+            total_rewards: self.total_rewards,
+            total_distributed: self.total_distributed,
+            emission_start: 0,
+            emission_total: 0,
+            proposal_count: 0,
+            guardian: Pubkey::default(),
+            paused: 0,
+            pending_authority: Pubkey::default(),
+            version: REWARD_POOL_VERSION,
+            reserved: [0; ACCOUNT_RESERVED_BYTES],
+        }
+    }
+
+    /// 基线的三项费率保存在奖励池中, 迁移时写入新建的参数配置, 其余参数取默认值
+    pub fn params(&self) -> RewardParams {
+        RewardParams {
+            upload_reward_rate: self.upload_reward_rate,
+            node_reward_rate: self.node_reward_rate,
+            seed_reward_rate: self.seed_reward_rate,
+            ..RewardParams::default()
+        }
+    }
+}
+
+#[account]
+pub struct RewardEpoch {
+    pub index: u64,
+    pub start: i64,
+    pub end: i64,
+    pub budget: u64,              // TF releasable during this epoch
+    pub distributed: u64,         // TF paid out during this epoch
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RewardConfig {
+    pub params: RewardParams,
+    pub updated_at: i64,
+    pub version: u8,              // REWARD_CONFIG_VERSION at last write
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES], // Zeroed space for future fields
+}
+
+impl RewardConfig {
+    pub fn apply(&mut self, params: &RewardParams, now: i64) -> Result<()> {
+        params.validate()?;
+        self.params = params.clone();
+        self.updated_at = now;
+        self.version = REWARD_CONFIG_VERSION;
+        Ok(())
+    }
+}
+
+/// 可由管理员调整的奖励参数
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct RewardParams {
+    pub upload_reward_rate: u64,  // TF per GB
+    pub node_reward_rate: u64,    // TF per hour
+    pub seed_reward_rate: u64,    // TF per hour
+    pub min_node_stake: u64,      // Minimum stake to operate a super node
+    pub min_uptime_percentage: u8,
+    pub unbonding_period: i64,    // Seconds between unstake and complete_unstake
+    pub slash_bps: u16,           // Share of stake slashed per offence
+    pub slash_reporter_reward: u64, // TF paid to the reporting watcher
+    pub slash_watcher_quorum: u8, // Watchers that must submit the same evidence before a slash
+    pub challenge_response_slots: u64, // Slots a challenged user has to respond
+    pub challenge_bond: u64,      // TF a challenger locks, lost if the target answers
+    pub fraud_report_bond: u64,   // TF a fraud reporter locks, lost if the report is rejected
+    pub fake_upload_penalty: u64, // TF forfeited for an upheld fake-upload report
+    pub fake_upload_reporter_bps: u16, // Reporter's share of the fake-upload penalty
+    pub spam_reporter_bounty: u64, // TF paid to the reporter of upheld spam
+    pub fraud_attestor_quorum: u8, // Matching attestor votes that resolve a fraud report
+    pub fraud_report_period: i64, // Seconds before an unresolved report can be expired
+    pub max_open_reports: u32,    // Pending reports a single reporter may have open
+    pub referral_reward: u64,     // TF paid per active invitee
+    pub referral_upload_threshold: u64, // Invitee bytes uploaded to count as active
+    pub referral_challenge_threshold: u32, // Invitee passed attestor challenges to count as active
+    pub max_referrals_per_referrer: u32,
+    pub vote_reward: u64,         // TF paid per governance vote
+    pub max_vote_rewards_per_epoch: u8, // Rewarded votes per voter per epoch
+    pub delegator_share_bps: u16, // Delegator's share of the delegate's vote reward
+    pub proposal_quorum: u64,     // Minimum yes + no weight for a proposal to pass
+    pub proposal_bond: u64,       // TF a proposer locks, lost if the proposal fails
+    pub speed_tiers: [SpeedTier; MAX_SPEED_TIERS], // Indexed by tier - 1
+    pub vip_monthly_price: u64,   // TF per SUBSCRIPTION_PERIOD
+    pub uptime_tiers: [BonusTier; MAX_BONUS_TIERS],
+    pub popularity_tiers: [BonusTier; MAX_BONUS_TIERS],
+}
+
+/// 加成档位: `value` 落在 [min, max] 时按 `multiplier`% 计算, multiplier 为 0 表示未启用
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct BonusTier {
+    pub min: u8,
+    pub max: u8,
+    pub multiplier: u16,
+}
+
+impl BonusTier {
+    const fn new(min: u8, max: u8, multiplier: u16) -> Self {
+        Self { min, max, multiplier }
+    }
+}
+
+impl Default for RewardParams {
+    fn default() -> Self {
+        Self {
+            upload_reward_rate: 2_000_000_000,     // 2 TF per GB (9 decimals)
+            node_reward_rate: 5_000_000_000,       // 5 TF per hour
+            seed_reward_rate: 100_000_000,         // 0.1 TF per hour
+            min_node_stake: 10_000_000_000_000,    // 10,000 TF
+            min_uptime_percentage: 90,
+            unbonding_period: 7 * 24 * 60 * 60, // 7 days
+            slash_bps: 1_000,                   // 10%
+            slash_reporter_reward: 20_000_000_000, // 20 TF
+            slash_watcher_quorum: 2,
+            challenge_response_slots: 9_000,    // ~1 hour
+            challenge_bond: 5_000_000_000,      // 5 TF
+            fraud_report_bond: 10_000_000_000,  // 10 TF
+            fake_upload_penalty: 100_000_000_000, // 100 TF
+            fake_upload_reporter_bps: 5_000,    // 50%
+            spam_reporter_bounty: 100_000_000_000, // 100 TF
+            fraud_attestor_quorum: 3,
+            fraud_report_period: 7 * 24 * 60 * 60, // 7 days
+            max_open_reports: 3,
+            referral_reward: 50_000_000_000,    // 50 TF
+            referral_upload_threshold: 10 * BYTES_PER_GB,
+            referral_challenge_threshold: 3,
+            max_referrals_per_referrer: 10,     // 500 TF cap
+            vote_reward: 1_000_000_000,         // 1 TF
+            max_vote_rewards_per_epoch: 3,
+            delegator_share_bps: 1_000,         // 10%
+            proposal_quorum: 100_000_000_000_000, // 100,000 TF
+            proposal_bond: 100_000_000_000,     // 100 TF
+            speed_tiers: [
+                SpeedTier::new(1_000_000_000, 9_000),  // Tier 1: 1 TF per ~hour
+                SpeedTier::new(3_000_000_000, 9_000),  // Tier 2: 3 TF per ~hour
+                SpeedTier::new(10_000_000_000, 9_000), // Tier 3: 10 TF per ~hour
+                SpeedTier::default(),
+            ],
+            vip_monthly_price: 500_000_000_000, // 500 TF
+            uptime_tiers: [
+                BonusTier::new(99, 100, 120), // 20% bonus for 99%+ uptime
+                BonusTier::new(95, 98, 110),  // 10% bonus for 95%+ uptime
+                BonusTier::default(),
+                BonusTier::default(),
+            ],
+            popularity_tiers: [
+                BonusTier::new(80, 100, 150), // 50% bonus for very popular files
+                BonusTier::new(50, 79, 125),  // 25% bonus for popular files
+                BonusTier::new(0, 20, 200),   // 100% bonus for rare files
+                BonusTier::default(),
+            ],
+        }
+    }
+}
+
+/// 下载加速档位: 每支付 `price` 获得 `duration_slots` 个 slot 的加速, price 为 0 表示未启用
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct SpeedTier {
+    pub price: u64,
+    pub duration_slots: u64,
+}
+
+impl SpeedTier {
+    const fn new(price: u64, duration_slots: u64) -> Self {
+        Self { price, duration_slots }
+    }
+}
+
+impl RewardParams {
+    /// 按档位 (从 1 开始) 查找已启用的加速价格
+    pub fn speed_tier(&self, tier: u8) -> Result<SpeedTier> {
+        let speed_tier = (tier as usize)
+            .checked_sub(1)
+            .and_then(|index| self.speed_tiers.get(index))
+            .copied()
+            .ok_or(ErrorCode::InvalidSpeedTier)?;
+        require!(speed_tier.price > 0, ErrorCode::InvalidSpeedTier);
+        Ok(speed_tier)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(self.min_uptime_percentage <= 100, ErrorCode::InvalidParams);
+        require!(self.unbonding_period >= 0, ErrorCode::InvalidParams);
+        require!(self.slash_bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidParams);
+        require!(self.challenge_response_slots > 0, ErrorCode::InvalidParams);
+        require!(
+            self.delegator_share_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidParams
+        );
+        require!(self.proposal_quorum > 0, ErrorCode::InvalidParams);
+        require!(self.fraud_attestor_quorum > 0, ErrorCode::InvalidParams);
+        require!(self.fraud_report_period > 0, ErrorCode::InvalidParams);
+        require!(self.max_open_reports > 0, ErrorCode::InvalidParams);
+        require!(self.slash_watcher_quorum > 0, ErrorCode::InvalidParams);
+        require!(
+            self.fake_upload_reporter_bps as u64 <= BPS_DENOMINATOR,
+            ErrorCode::InvalidParams
+        );
+        for tier in self.speed_tiers.iter() {
+            require!(
+                tier.price == 0 || tier.duration_slots > 0,
+                ErrorCode::InvalidParams
+            );
+        }
+        for tier in self.uptime_tiers.iter().chain(self.popularity_tiers.iter()) {
+            if tier.multiplier == 0 {
+                continue;
+            }
+            require!(
+                tier.min <= tier.max
+                    && tier.max <= 100
+                    && tier.multiplier <= MAX_BONUS_MULTIPLIER,
+                ErrorCode::InvalidParams
+            );
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserAccount {
+    pub owner: Pubkey,
+    pub balance: u64,             // Available TF balance
+    pub staked_amount: u64,       // Staked TF for super node
+    pub total_uploaded: u64,      // Total bytes uploaded (attested)
+    pub total_consumed: u64,      // Total TF consumed for speed
+    pub seeding_hours: u64,       // Total hours seeding
+    pub node_operation_hours: u64, // Total hours operating super node
+    pub reputation_score: u32,    // User reputation (0-1000)
+    pub reputation_updated_at: i64, // Last time decay was applied
+    pub upload_nonce: u64,        // Last consumed upload receipt nonce
+    pub slash_count: u32,         // Number of times stake has been slashed
+    pub challenges_passed: u32,   // Possession challenges answered in time
+    pub challenges_failed: u32,   // Possession challenges left to expire
+    pub referrer: Pubkey,         // Inviting wallet, default if none
+    pub referral_paid: bool,      // Referral reward for this user already paid
+    pub referrals_paid: u32,      // Referral rewards this user has received
+    pub governance_locked_until: i64, // Withdrawals blocked until voting ends
+    pub version: u8,              // USER_ACCOUNT_VERSION at last write
+    pub banned: bool,             // Permanently barred after an upheld spam report
+    pub challenge_bumped_at: i64, // Last reputation bump from a passed challenge
+    pub open_escrows: u32,        // Open channels, auctions, challenges and reports involving this account
+    pub pending_reports: u32,     // Unresolved fraud reports against this account
+    pub service_bumped_at: i64,   // Last reputation bump from a service reward
+    pub attested_challenges_passed: u32, // Passed challenges issued by an attestor
+    pub vote_reward_epoch: u64,   // Epoch of the last vote reward
+    pub vote_rewards_in_epoch: u8, // Vote rewards taken in vote_reward_epoch
+    pub active_seedings: u32,     // Open SeederRecord accounts owned by this user
+    pub open_reports: u32,        // Pending fraud reports filed by this user
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 46], // Zeroed space for future fields
+}
+
+/// 已部署的基线版本 UserAccount 布局 (无版本字段), 仅用于迁移。
+/// 基线按 `8 + size_of` 分配空间, 数据末尾有对齐填充。
+#[derive(AnchorDeserialize)]
+pub struct LegacyUserAccount {
+    pub owner: Pubkey,
+    pub balance: u64,
+    pub staked_amount: u64,
+    pub total_uploaded: u64,      // Total GB uploaded
+    pub total_consumed: u64,
+    pub seeding_hours: u64,
+    pub node_operation_hours: u64,
+    pub reputation_score: u32,
+}
+
+impl LegacyUserAccount {
+    /// 基线之后新增的字段均取初始值, 上传量由 GB 换算为字节
+    pub fn upgrade(self) -> UserAccount {
+        UserAccount {
+            owner: self.owner,
+            balance: self.balance,
+            staked_amount: self.staked_amount,
+            total_uploaded: self.total_uploaded.saturating_mul(BYTES_PER_GB),
+            total_consumed: self.total_consumed,
+            seeding_hours: self.seeding_hours,
+            node_operation_hours: self.node_operation_hours,
+            reputation_score: self.reputation_score.min(REPUTATION_MAX),
+            reputation_updated_at: 0,
+            upload_nonce: 0,
+            slash_count: 0,
+            challenges_passed: 0,
+            challenges_failed: 0,
+            referrer: Pubkey::default(),
+            referral_paid: false,
+            referrals_paid: 0,
+            governance_locked_until: 0,
+            version: USER_ACCOUNT_VERSION,
+            banned: false,
+            challenge_bumped_at: 0,
+            open_escrows: 0,
+            pending_reports: 0,
+            service_bumped_at: 0,
+            attested_challenges_passed: 0,
+            vote_reward_epoch: 0,
+            vote_rewards_in_epoch: 0,
+            active_seedings: 0,
+            open_reports: 0,
+            reserved: [0; ACCOUNT_RESERVED_BYTES - 46],
+        }
+    }
+}
+
+impl UserAccount {
+    /// 依次从余额、质押和解锁队列中扣除至多 `amount`, 返回实际扣除数量
+    pub fn forfeit(&mut self, amount: u64, unbonding: Option<&mut Unbonding>) -> u64 {
+        let from_balance = amount.min(self.balance);
+        self.balance -= from_balance;
+        let mut taken = from_balance;
+
+        let from_stake = (amount - taken).min(self.staked_amount);
+        self.staked_amount -= from_stake;
+        taken += from_stake;
+
+        if let Some(unbonding) = unbonding {
+            let from_unbonding = (amount - taken).min(unbonding.amount);
+            unbonding.amount -= from_unbonding;
+            taken += from_unbonding;
+        }
+        taken
+    }
+
+    /// 按经过的衰减周期扣减信誉分
+    pub fn decay_reputation(&mut self, now: i64) {
+        if self.reputation_updated_at == 0 {
+            self.reputation_updated_at = now;
+            return;
+        }
+        let periods = now.saturating_sub(self.reputation_updated_at) / REPUTATION_DECAY_INTERVAL;
+        if periods > 0 {
+            let decay = (periods as u64).saturating_mul(REPUTATION_DECAY_POINTS as u64);
+            self.reputation_score = (self.reputation_score as u64).saturating_sub(decay) as u32;
+            self.reputation_updated_at += periods * REPUTATION_DECAY_INTERVAL;
+        }
+    }
+
+    /// 先应用衰减, 再按 `delta` 调整信誉分, 结果限制在 [0, REPUTATION_MAX]
+    pub fn adjust_reputation(&mut self, delta: i32, now: i64) {
+        self.decay_reputation(now);
+        let score = (self.reputation_score as i64 + delta as i64).clamp(0, REPUTATION_MAX as i64);
+        self.reputation_score = score as u32;
+    }
+
+    /// 服务奖励的信誉加分, 每 REPUTATION_SERVICE_BUMP_INTERVAL 至多一次,
+    /// 防止把奖励拆成多次小额领取来刷分
+    pub fn bump_service_reputation(&mut self, now: i64) {
+        if now >= self.service_bumped_at.saturating_add(REPUTATION_SERVICE_BUMP_INTERVAL) {
+            self.adjust_reputation(REPUTATION_SERVICE_BUMP, now);
+            self.service_bumped_at = now;
+        }
+    }
+
+    /// 记录一次通过的持有挑战, 证明者发起的挑战另行计数
+    pub fn record_challenge_passed(&mut self, attested: bool) -> Result<()> {
+        self.challenges_passed = self.challenges_passed
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if attested {
+            self.attested_challenges_passed = self.attested_challenges_passed
+                .checked_add(1)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        Ok(())
+    }
+
+    /// 邀请活跃度: 仅认可独立方证明的活动, 即回执证明的上传量或证明者发起并通过的挑战。
+    /// 任何人都能发起挑战, 自己安排的挑战不计入。
+    pub fn referral_active(&self, params: &RewardParams) -> bool {
+        self.total_uploaded >= params.referral_upload_threshold
+            || self.attested_challenges_passed >= params.referral_challenge_threshold
+    }
+
+    /// 占用本纪元的一次投票奖励名额, 每个纪元至多 `max` 次, 防止批量创建提案刷奖励
+    pub fn take_vote_reward(&mut self, epoch: u64, max: u8) -> bool {
+        if self.vote_reward_epoch != epoch {
+            self.vote_reward_epoch = epoch;
+            self.vote_rewards_in_epoch = 0;
+        }
+        if self.vote_rewards_in_epoch >= max {
+            return false;
+        }
+        self.vote_rewards_in_epoch += 1;
+        true
+    }
+
+    /// 登记一项结算时需要本账户的未结事项 (通道、拍卖、挑战、举报)
+    pub fn open_escrow(&mut self) -> Result<()> {
+        self.open_escrows = self.open_escrows
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn release_escrow(&mut self) {
+        self.open_escrows = self.open_escrows.saturating_sub(1);
+    }
+
+    /// 举报者同时未裁决的举报数不能超过 `max`, 防止单个举报者冻结大量账户
+    pub fn open_report(&mut self, max: u32) -> Result<()> {
+        require!(self.open_reports < max, ErrorCode::TooManyOpenReports);
+        self.open_reports += 1;
+        Ok(())
+    }
+
+    pub fn close_report(&mut self) {
+        self.open_reports = self.open_reports.saturating_sub(1);
+    }
+
+    pub fn open_seeding(&mut self) -> Result<()> {
+        self.active_seedings = self.active_seedings
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn close_seeding(&mut self) {
+        self.active_seedings = self.active_seedings.saturating_sub(1);
+    }
+
+    /// 被封禁账户不能再获得奖励或建立新的承诺 (质押、托管、订阅、做种、挑战、举报、治理等)。
+    /// 已有承诺的结算和退出路径保持开放, 以免冻结对手方的资金: 停止做种、应答或过期挑战、
+    /// 取消订阅、注销节点、拍卖和通道结算、解除质押、提现和撤销委托。
+    pub fn require_not_banned(&self) -> Result<()> {
+        require!(!self.banned, ErrorCode::AccountBanned);
+        Ok(())
+    }
+
+    /// 被举报且尚未裁决时冻结所有流出 (提现、关闭、托管、消费、保证金、解除质押),
+    /// 保证裁决成立时仍有可罚没的余额
+    pub fn require_not_frozen(&self) -> Result<()> {
+        require!(self.pending_reports == 0, ErrorCode::AccountFrozen);
+        Ok(())
+    }
+
+    /// 投票锁定期内禁止把余额转到其他账户 (提现、关闭、拍卖托管、通道押金)。
+    /// 消费和保证金只流向奖励池或退回本人, 不受限制。
+    pub fn require_unlocked(&self, now: i64) -> Result<()> {
+        require!(now >= self.governance_locked_until, ErrorCode::GovernanceLocked);
+        Ok(())
+    }
+
+    /// 治理投票权重: 余额加质押, 达到超级节点质押门槛时加倍
+    pub fn voting_weight(&self, min_node_stake: u64) -> Result<u64> {
+        let weight = self.balance
+            .checked_add(self.staked_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.staked_amount >= min_node_stake {
+            weight
+                .checked_mul(SUPER_NODE_VOTE_MULTIPLIER)
+                .ok_or_else(|| error!(ErrorCode::MathOverflow))
+        } else {
+            Ok(weight)
+        }
+    }
+
+    /// 信誉决定奖励加成系数的上限: 0 分仅能获得基础奖励 (100%),
+    /// 满分可获得最高加成 (MAX_BONUS_MULTIPLIER%)
+    pub fn reputation_multiplier_cap(&self) -> u64 {
+        let score = self.reputation_score.min(REPUTATION_MAX) as u64;
+        100 + score * (MAX_BONUS_MULTIPLIER as u64 - 100) / REPUTATION_MAX as u64
+    }
+}
+
+/// 已关闭用户账户的永久记录, 防止重新创建账户来重置回执 nonce 和邀请状态
+#[account]
+#[derive(InitSpace)]
+pub struct ClosedUser {
+    pub owner: Pubkey,
+    pub closed_at: i64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub kind: ProposalKind,
+    pub params: Option<RewardParams>, // Applied on execution of EconomicParams proposals
+    pub description_hash: [u8; 32], // Hash of the off-chain proposal text
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub executable_at: i64,       // voting_ends_at + timelock
+    pub expires_at: i64,          // executable_at + PROPOSAL_EXECUTION_WINDOW
+    pub config_updated_at: i64,   // RewardConfig.updated_at at creation
+    pub bond: u64,                // TF held from the proposer's balance
+    pub bond_settled: bool,       // Bond refunded or moved to the pool
+    pub yes_weight: u64,
+    pub no_weight: u64,
+    pub executed: bool,
+    pub quorum: u64,              // proposal_quorum at creation
+    pub version: u8,              // PROPOSAL_VERSION at creation
+    pub reserved: [u8; ACCOUNT_RESERVED_BYTES - 25], // Zeroed space for future fields
+}
+
+impl Proposal {
+    pub fn record_vote(&mut self, approve: bool, weight: u64) -> Result<()> {
+        let tally = if approve {
+            &mut self.yes_weight
+        } else {
+            &mut self.no_weight
+        };
+        *tally = tally.checked_add(weight).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    /// 总投票权重达到法定人数, 且赞成票占比达到提案类型的门槛即通过
+    pub fn passed(&self) -> bool {
+        let total = self.yes_weight as u128 + self.no_weight as u128;
+        total > 0
+            && total >= self.quorum as u128
+            && self.yes_weight as u128 * BPS_DENOMINATOR as u128
+                >= total * self.kind.threshold_bps() as u128
+    }
+
+    /// 执行前检查: 已通过、时间锁已过且未超过执行期限。
+    /// 参数提案保存的是完整参数快照, 创建后配置若已被修改则拒绝执行, 避免覆盖之后的调整。
+    pub fn check_executable(&self, now: i64, config_updated_at: i64) -> Result<()> {
+        require!(!self.executed, ErrorCode::ProposalAlreadyExecuted);
+        require!(now >= self.executable_at, ErrorCode::TimelockNotElapsed);
+        require!(now < self.expires_at, ErrorCode::ProposalExpired);
+        require!(self.passed(), ErrorCode::ProposalNotPassed);
+        if self.params.is_some() {
+            require!(
+                config_updated_at == self.config_updated_at,
+                ErrorCode::ProposalConfigChanged
+            );
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ProposalKind {
+    EconomicParams,
+    ProtocolUpgrade,
+}
+
+impl ProposalKind {
+    pub fn threshold_bps(&self) -> u64 {
+        match self {
+            ProposalKind::EconomicParams => 5_100,  // 51%
+            ProposalKind::ProtocolUpgrade => 6_670, // 66.7%
+        }
+    }
+
+    pub fn timelock(&self) -> i64 {
+        match self {
+            ProposalKind::EconomicParams => 7 * 24 * 60 * 60,   // 7 days
+            ProposalKind::ProtocolUpgrade => 30 * 24 * 60 * 60, // 30 days
+        }
+    }
+}
+
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,            // Voting UserAccount
+    pub approve: bool,
+    pub weight: u64,
+    pub delegate: Pubkey,         // UserAccount that cast it on the voter's behalf, default if direct
+    pub reward: u64,              // Vote reward paid to the voter
+    pub shared: u64,              // Part of reward passed on to delegators
+}
+
+impl VoteRecord {
+    /// 从本次投票奖励中取出一份委托人分成, 累计不超过奖励本身
+    pub fn take_delegator_share(&mut self, share_bps: u16) -> u64 {
+        let share = (self.reward as u128 * share_bps as u128 / BPS_DENOMINATOR as u128) as u64;
+        let share = share.min(self.reward - self.shared);
+        self.shared += share;
+        share
+    }
+}
+
+#[account]
+pub struct Delegation {
+    pub delegator: Pubkey,        // Delegating UserAccount
+    pub delegate: Pubkey,         // UserAccount voting on its behalf
+    pub created_at: i64,
+}
+
+#[account]
+pub struct Content {
+    pub info_hash: [u8; 32],
+    pub merkle_root: [u8; 32],    // Root over piece hashes
+    pub piece_count: u32,
+    pub registered_by: Pubkey,    // Attestor that registered the root
+    pub registered_at: i64,
+    pub size_bytes: u64,
+    pub active_seeders: u32,      // Open SeederRecord accounts
+    pub download_count: u64,      // Downloads reported by attestors
+}
+
+impl Content {
+    /// 热度 (0-100), 按累计下载次数线性增长
+    pub fn popularity(&self) -> u8 {
+        (self.download_count.min(POPULARITY_FULL_DOWNLOADS) * 100 / POPULARITY_FULL_DOWNLOADS) as u8
+    }
+
+    /// 稀缺系数 (1-5x), 做种者越少越高
+    pub fn rarity_multiplier(&self) -> u64 {
+        match self.active_seeders {
+            0..=1 => 5,
+            2..=4 => 4,
+            5..=9 => 3,
+            10..=49 => 2,
+            _ => 1,
+        }
+    }
+}
+
+#[account]
+pub struct SeederRecord {
+    pub owner: Pubkey,
+    pub info_hash: [u8; 32],
+    pub joined_at: i64,
+    pub claimed_until: i64,       // Seeding time before this has been rewarded
+}
+
+#[account]
+pub struct Challenge {
+    pub challenger: Pubkey,
+    pub target: Pubkey,           // Challenged UserAccount
+    pub info_hash: [u8; 32],
+    pub piece_index: u32,
+    pub issued_slot: u64,
+    pub deadline_slot: u64,
+    pub bond: u64,                // TF held from the challenger's balance
+    pub attested: bool,           // Issued by a registered attestor
+}
+
+#[account]
+pub struct Watcher {
+    pub key: Pubkey,              // Key allowed to submit slashing evidence
+    pub registered_at: i64,
+}
+
+#[account]
+pub struct SlashRecord {
+    pub node: Pubkey,             // Slashed UserAccount
+    pub reporter: Pubkey,         // Watcher that first submitted the evidence
+    pub evidence_hash: [u8; 32],
+    pub approvals: u8,            // Watchers that have submitted this evidence
+    pub amount: u64,
+    pub slashed_at: i64,          // 0 until the quorum is reached
+}
+
+#[account]
+pub struct SlashVote {
+    pub record: Pubkey,           // SlashRecord
+    pub watcher: Pubkey,
+    pub voted_at: i64,
+}
+
+#[account]
+pub struct FraudReport {
+    pub reporter: Pubkey,         // Reporter wallet
+    pub target: Pubkey,           // Reported UserAccount
+    pub kind: FraudKind,
+    pub evidence_hash: [u8; 32],
+    pub bond: u64,                // TF held from the reporter's balance
+    pub status: FraudStatus,
+    pub reported_at: i64,
+    pub resolved_by: Pubkey,      // Authority, the deciding attestor, or default if expired
+    pub resolved_at: i64,
+    pub uphold_votes: u8,         // Attestor votes to uphold
+    pub reject_votes: u8,         // Attestor votes to reject
+    pub expires_at: i64,          // Pending reports can be expired from this time
+}
+
+impl FraudReport {
+    /// 按裁决结果结算双方账户, 返回 (罚没数量, 举报者奖励)
+    ///
+    /// 成立时按白皮书罚则罚没被举报账户, 向举报者退还保证金并发放奖励; 不成立时保证金不退还。
+    /// 两种情况都解除被举报账户的冻结, 并释放双方的托管和举报计数。
+    pub fn settle(
+        &mut self,
+        upheld: bool,
+        params: &RewardParams,
+        target_account: &mut UserAccount,
+        target_unbonding: Option<&mut Unbonding>,
+        reporter_account: &mut UserAccount,
+        now: i64,
+    ) -> Result<(u64, u64)> {
+        require!(self.status == FraudStatus::Pending, ErrorCode::FraudReportResolved);
+
+        let (penalty, bounty) = if upheld {
+            let (penalty, bounty) = match self.kind {
+                FraudKind::FakeUpload => {
+                    let penalty = target_account.forfeit(params.fake_upload_penalty, target_unbonding);
+                    let bounty = penalty as u128 * params.fake_upload_reporter_bps as u128
+                        / BPS_DENOMINATOR as u128;
+                    (penalty, bounty as u64)
+                }
+                FraudKind::Spam => {
+                    target_account.banned = true;
+                    let penalty = target_account.forfeit(u64::MAX, target_unbonding);
+                    (penalty, params.spam_reporter_bounty.min(penalty))
+                }
+            };
+            target_account.adjust_reputation(-REPUTATION_SLASH_PENALTY, now);
+
+            reporter_account.balance = reporter_account.balance
+                .checked_add(self.bond)
+                .and_then(|balance| balance.checked_add(bounty))
+                .ok_or(ErrorCode::MathOverflow)?;
+            self.status = FraudStatus::Upheld;
+            (penalty, bounty)
+        } else {
+            self.status = FraudStatus::Rejected;
+            (0, 0)
+        };
+        target_account.release_escrow();
+        target_account.pending_reports = target_account.pending_reports.saturating_sub(1);
+        reporter_account.release_escrow();
+        reporter_account.close_report();
+
+        Ok((penalty, bounty))
+    }
+}
+
+/// 证明者对举报的投票记录, 防止同一证明者重复投票
+#[account]
+pub struct FraudVote {
+    pub report: Pubkey,
+    pub attestor: Pubkey,
+    pub upheld: bool,
+    pub voted_at: i64,
+}
+
+/// 白皮书惩罚表中的违规类型
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FraudKind {
+    FakeUpload,
+    Spam,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FraudStatus {
+    Pending,
+    Upheld,
+    Rejected,
+}
+
+#[account]
+pub struct SpeedBoost {
+    pub owner: Pubkey,
+    pub tier: u8,                 // Priority tier, higher is faster
+    pub expires_at_slot: u64,     // Boost is active while Clock.slot < this
+}
+
+#[account]
+pub struct Subscription {
+    pub owner: Pubkey,
+    pub expires_at: i64,          // VIP access is active while now < this
+    pub auto_renew_allowance: u64, // TF a crank may still spend on renewals
+}
+
+impl Subscription {
+    /// 从当前到期时间 (已过期则从现在) 起延长一个订阅周期
+    pub fn extend(&mut self, now: i64) -> Result<()> {
+        self.expires_at = self.expires_at
+            .max(now)
+            .checked_add(SUBSCRIPTION_PERIOD)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Multisig {
+    pub creator: Pubkey,
+    #[max_len(10)]
+    pub signers: Vec<Pubkey>,     // Member keys
+    pub threshold: u8,            // Member signatures required
+}
+
+impl Multisig {
+    pub fn set_signers(&mut self, signers: Vec<Pubkey>, threshold: u8) -> Result<()> {
+        require!(
+            threshold > 0
+                && threshold as usize <= signers.len()
+                && signers.len() <= MAX_MULTISIG_SIGNERS,
+            ErrorCode::InvalidMultisig
+        );
+        for (i, signer) in signers.iter().enumerate() {
+            require!(!signers[..i].contains(signer), ErrorCode::InvalidMultisig);
+        }
+
+        self.signers = signers;
+        self.threshold = threshold;
+        Ok(())
+    }
+
+    /// 统计 `accounts` 中已签名的不同成员, 需达到阈值
+    pub fn verify(&self, accounts: &[AccountInfo]) -> Result<()> {
+        let mut approved: Vec<Pubkey> = Vec::with_capacity(self.signers.len());
+        for account in accounts {
+            if account.is_signer
+                && self.signers.contains(account.key)
+                && !approved.contains(account.key)
+            {
+                approved.push(*account.key);
+            }
+        }
+        require!(
+            approved.len() >= self.threshold as usize,
+            ErrorCode::NotEnoughSigners
+        );
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SuperNode {
+    pub owner: Pubkey,
+    #[max_len(64)]
+    pub peer_id: String,          // libp2p peer id (base58)
+    #[max_len(4, 128)]
+    pub multiaddrs: Vec<String>,  // Dialable libp2p multiaddrs
+    pub bandwidth_tier: BandwidthTier,
+    #[max_len(16)]
+    pub region: String,           // Region code, e.g. "cn-east"
+    pub registered_at: i64,
+    pub updated_at: i64,
+}
+
+#[account]
+pub struct NodeUptime {
+    pub owner: Pubkey,
+    pub heartbeats: [u8; (UPTIME_WINDOW_HOURS / 8) as usize], // Ring bitmap, one bit per hour
+    pub last_heartbeat_hour: u64, // Latest hour with a recorded heartbeat
+    pub claimed_until_hour: u64,  // Hours before this have been rewarded
+}
+
+impl NodeUptime {
+    fn slot(hour: u64) -> (usize, u8) {
+        let index = hour % UPTIME_WINDOW_HOURS;
+        ((index / 8) as usize, 1 << (index % 8))
+    }
+
+    /// 记录某小时的心跳, 同一小时重复上报返回 false
+    pub fn record(&mut self, hour: u64) -> bool {
+        if hour <= self.last_heartbeat_hour {
+            return false;
+        }
+
+        // 清除环形缓冲中被跳过小时的旧记录
+        let skipped = (hour - self.last_heartbeat_hour - 1).min(UPTIME_WINDOW_HOURS);
+        for stale in hour - skipped..hour {
+            let (byte, mask) = Self::slot(stale);
+            self.heartbeats[byte] &= !mask;
+        }
+
+        let (byte, mask) = Self::slot(hour);
+        self.heartbeats[byte] |= mask;
+        self.last_heartbeat_hour = hour;
+        true
+    }
+
+    pub fn is_online(&self, hour: u64) -> bool {
+        if hour > self.last_heartbeat_hour
+            || self.last_heartbeat_hour - hour >= UPTIME_WINDOW_HOURS
+        {
+            return false;
+        }
+        let (byte, mask) = Self::slot(hour);
+        self.heartbeats[byte] & mask != 0
+    }
+
+    /// 统计 [from_hour, to_hour) 内有心跳的小时数
+    pub fn online_hours(&self, from_hour: u64, to_hour: u64) -> u64 {
+        (from_hour..to_hour).filter(|&hour| self.is_online(hour)).count() as u64
+    }
+}
+
+impl SuperNode {
+    pub fn set_info(
+        &mut self,
+        peer_id: String,
+        multiaddrs: Vec<String>,
+        bandwidth_tier: BandwidthTier,
+        region: String,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            !peer_id.is_empty() && peer_id.len() <= MAX_PEER_ID_LEN,
+            ErrorCode::InvalidNodeInfo
+        );
+        require!(
+            !multiaddrs.is_empty()
+                && multiaddrs.len() <= MAX_MULTIADDRS
+                && multiaddrs
+                    .iter()
+                    .all(|addr| !addr.is_empty() && addr.len() <= MAX_MULTIADDR_LEN),
+            ErrorCode::InvalidNodeInfo
+        );
+        require!(region.len() <= MAX_REGION_LEN, ErrorCode::InvalidNodeInfo);
+
+        self.peer_id = peer_id;
+        self.multiaddrs = multiaddrs;
+        self.bandwidth_tier = bandwidth_tier;
+        self.region = region;
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+/// 超级节点声明的带宽等级
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum BandwidthTier {
+    Home,                         // >= 100 Mbps
+    Backbone,                     // >= 1 Gbps
+}
+
+#[account]
+pub struct BandwidthAuction {
+    pub downloader: Pubkey,       // Downloader wallet
+    pub auction_id: u64,
+    pub info_hash: [u8; 32],
+    pub size_gb: u64,
+    pub max_price_per_gb: u64,
+    pub escrow: u64,              // TF held out of the downloader's balance
+    pub bid_deadline: i64,
+    pub delivery_period: i64,     // Seconds the winner has to deliver
+    pub delivery_deadline: i64,   // Set when bidding closes
+    pub winner: Pubkey,           // Lowest bidding node wallet so far
+    pub winning_price_per_gb: u64,
+    pub awarded: bool,
+}
+
+impl BandwidthAuction {
+    /// 将托管款支付给中标节点并释放双方的未结记录, 调用方负责关闭拍卖账户
+    pub fn pay_winner(
+        &self,
+        auction: Pubkey,
+        node_account: &mut UserAccount,
+        downloader_account: &mut UserAccount,
+    ) -> Result<()> {
+        require!(self.awarded, ErrorCode::InvalidAuction);
+
+        node_account.balance = node_account.balance
+            .checked_add(self.escrow)
+            .ok_or(ErrorCode::MathOverflow)?;
+        node_account.release_escrow();
+        downloader_account.release_escrow();
+
+        emit!(AuctionSettledEvent {
+            auction,
+            winner: self.winner,
+            paid: self.escrow,
+            refunded: 0,
+        });
+
+        Ok(())
+    }
+}
+
+/// 证明者签发的带宽交付回执, 签名消息为其 Borsh 序列化结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DeliveryReceipt {
+    pub auction: Pubkey,
+    pub winner: Pubkey,
+    pub info_hash: [u8; 32],
+    pub size_gb: u64,             // Must cover the whole auctioned size
+}
+
+#[account]
+pub struct PaymentChannel {
+    pub payer: Pubkey,            // Downloader wallet funding the channel
+    pub payee: Pubkey,            // Super node wallet receiving vouchers
+    pub channel_id: u64,          // Payer-chosen id, part of the PDA seeds
+    pub deposit: u64,             // TF locked out of the payer's balance
+    pub claimed_amount: u64,      // Highest cumulative voucher submitted so far
+    pub closing: bool,
+    pub dispute_ends_at: i64,
+    pub opened_at: i64,
+}
+
+impl PaymentChannel {
+    /// 校验付款方对累计金额 `amount` 的链下凭证签名
+    pub fn verify_voucher(
+        &self,
+        channel: Pubkey,
+        instructions: &AccountInfo,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount <= self.deposit, ErrorCode::InvalidVoucher);
+        let voucher = ChannelVoucher {
+            channel,
+            channel_id: self.channel_id,
+            opened_at: self.opened_at,
+            amount,
+        };
+        verify_ed25519_signature(instructions, &self.payer, &voucher.try_to_vec()?)
+    }
+}
+
+/// 付款方签发的链下累计支付凭证, 签名消息为其 Borsh 序列化结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ChannelVoucher {
+    pub channel: Pubkey,
+    pub channel_id: u64,
+    pub opened_at: i64,           // Distinguishes a re-opened channel at the same address
+    pub amount: u64,              // Cumulative TF owed to the payee
+}
+
+#[account]
+pub struct Unbonding {
+    pub owner: Pubkey,
+    pub amount: u64,              // TF waiting to return to balance
+    pub unlock_at: i64,           // Unix timestamp the cooldown ends
+    pub slash_count: u32,         // UserAccount.slash_count when queued
+}
+
+#[account]
+pub struct Attestor {
+    pub key: Pubkey,              // Ed25519 key that signs upload receipts
+    pub registered_at: i64,
+}
+
+/// 证明者签发的上传回执, 签名消息为其 Borsh 序列化结果
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct UploadReceipt {
+    pub user: Pubkey,
+    pub bytes: u64,
+    pub info_hash: [u8; 32],
+    pub nonce: u64,
+}
+
+// Context definitions
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = reward_pool,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateParams<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartEmission<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct OpenEpoch<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<RewardEpoch>(),
+        seeds = [b"epoch", index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: 管理员 (密钥或多签账户) 或守护者, 由 set_pause 校验签名
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetGuardian<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: 待接收的管理员密钥或多签账户, 由 authorize 校验签名
+    pub new_authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Multisig::INIT_SPACE,
+        seeds = [b"multisig", creator.key().as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMultisig<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref()],
+        bump
+    )]
+    pub multisig: Account<'info, Multisig>,
+}
+
+#[derive(Accounts)]
+pub struct FundPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = vault
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.mint,
+        token::authority = funder
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(attestor_key: Pubkey)]
+pub struct RegisterAttestor<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Attestor>(),
+        seeds = [b"attestor", attestor_key.as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAttestor<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"attestor", attestor.key.as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(watcher_key: Pubkey)]
+pub struct AddWatcher<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Watcher>(),
+        seeds = [b"watcher", watcher_key.as_ref()],
+        bump
+    )]
+    pub watcher: Account<'info, Watcher>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWatcher<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"watcher", watcher.key.as_ref()],
+        bump
+    )]
+    pub watcher: Account<'info, Watcher>,
+
+    /// CHECK: 管理员密钥或多签账户, 由 authorize 校验签名
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUser<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: 关闭记录地址, 必须不存在 (已关闭的钱包不能重新创建账户)
+    #[account(
+        seeds = [b"closed_user", user.key().as_ref()],
+        bump
+    )]
+    pub closed_user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"user", referrer_account.owner.as_ref()],
+        bump
+    )]
+    pub referrer_account: Option<Account<'info, UserAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRewardPool<'info> {
+    /// CHECK: 基线布局无法按当前结构反序列化, 由 migrate_reward_pool 校验判别符和管理员
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardConfig::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = mint,
+        token::authority = reward_pool,
+        seeds = [b"vault"],
+        bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUserAccount<'info> {
+    /// CHECK: 基线布局无法按当前结构反序列化, 由 migrate_user_account 校验判别符和所有者
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"user", owner.key().as_ref()],
+        bump
+    )]
+    pub user_account: UncheckedAccount<'info>,
+
+    /// CHECK: 仅用于推导用户账户地址
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralReward<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_epoch.index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", invitee_account.owner.as_ref()],
+        bump,
+        constraint = invitee_account.referrer != Pubkey::default() @ ErrorCode::InvalidReferrer
+    )]
+    pub invitee_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", invitee_account.referrer.as_ref()],
+        bump
+    )]
+    pub referrer_account: Account<'info, UserAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(receipt: UploadReceipt)]
+pub struct RewardUpload<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_epoch.index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: 关闭记录地址, 必须不存在 (已关闭的钱包不能重新创建账户)
+    #[account(
+        seeds = [b"closed_user", user.key().as_ref()],
+        bump
+    )]
+    pub closed_user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"attestor", attestor.key.as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    #[account(
+        seeds = [b"content", receipt.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// CHECK: 指令 sysvar, 由 address 约束校验
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RewardNode<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_epoch.index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"uptime", user.key().as_ref()],
+        bump
+    )]
+    pub node_uptime: Account<'info, NodeUptime>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RewardSeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_epoch.index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"content", content.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(
+        mut,
+        seeds = [b"seeder", content.info_hash.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub seeder: Account<'info, SeederRecord>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(info_hash: [u8; 32])]
+pub struct RegisterContent<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"attestor", signer.key().as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    #[account(
+        init,
+        payer = signer,
+        space = 8 + std::mem::size_of::<Content>(),
+        seeds = [b"content", info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordDownloads<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"attestor", signer.key().as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    #[account(
+        mut,
+        seeds = [b"content", content.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartSeeding<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"content", content.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + std::mem::size_of::<SeederRecord>(),
+        seeds = [b"seeder", content.info_hash.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub seeder: Account<'info, SeederRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StopSeeding<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"content", content.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"seeder", content.info_hash.as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub seeder: Account<'info, SeederRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct IssueChallenge<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        seeds = [b"content", content.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(
+        mut,
+        seeds = [b"user", target_account.owner.as_ref()],
+        bump
+    )]
+    pub target_account: Account<'info, UserAccount>,
+
+    // 只能挑战声明正在做种该内容的用户
+    #[account(
+        seeds = [b"seeder", content.info_hash.as_ref(), target_account.owner.as_ref()],
+        bump
+    )]
+    pub seeder: Account<'info, SeederRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"user", challenger.key().as_ref()],
+        bump
+    )]
+    pub challenger_account: Account<'info, UserAccount>,
+
+    // 由已注册证明者发起时提供, 只有这类挑战计入邀请活跃度
+    #[account(
+        seeds = [b"attestor", challenger.key().as_ref()],
+        bump
+    )]
+    pub attestor: Option<Account<'info, Attestor>>,
+
+    #[account(
+        init,
+        payer = challenger,
+        space = 8 + std::mem::size_of::<Challenge>(),
+        seeds = [b"challenge", target_account.key().as_ref(), content.info_hash.as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    /// CHECK: SlotHashes sysvar, 由 address 约束校验
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RespondChallenge<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"content", challenge.info_hash.as_ref()],
+        bump
+    )]
+    pub content: Account<'info, Content>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        close = challenger,
+        has_one = challenger,
+        constraint = challenge.target == user_account.key() @ ErrorCode::InvalidChallenge,
+        seeds = [b"challenge", user_account.key().as_ref(), challenge.info_hash.as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(
+        mut,
+        seeds = [b"user", challenge.challenger.as_ref()],
+        bump
+    )]
+    pub challenger_account: Account<'info, UserAccount>,
+
+    /// CHECK: 仅接收关闭挑战账户返还的租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub challenger: UncheckedAccount<'info>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireChallenge<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", target_account.owner.as_ref()],
+        bump
+    )]
+    pub target_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        close = challenger,
+        has_one = challenger,
+        constraint = challenge.target == target_account.key() @ ErrorCode::InvalidChallenge,
+        seeds = [b"challenge", target_account.key().as_ref(), challenge.info_hash.as_ref()],
+        bump
+    )]
+    pub challenge: Account<'info, Challenge>,
+
+    #[account(
+        mut,
+        seeds = [b"user", challenge.challenger.as_ref()],
+        bump
+    )]
+    pub challenger_account: Account<'info, UserAccount>,
+
+    /// CHECK: 仅接收关闭挑战账户返还的租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub challenger: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConsumeTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<SpeedBoost>(),
+        seeds = [b"speed_boost", user.key().as_ref()],
+        bump
+    )]
+    pub speed_boost: Account<'info, SpeedBoost>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Subscribe<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<Subscription>(),
+        seeds = [b"subscription", user.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RenewSubscription<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", subscription.owner.as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription.owner.as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", user.key().as_ref()],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterSuperNode<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + SuperNode::INIT_SPACE,
+        seeds = [b"super_node", user.key().as_ref()],
+        bump
+    )]
+    pub super_node: Account<'info, SuperNode>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateSuperNode<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"super_node", user.key().as_ref()],
+        bump
+    )]
+    pub super_node: Account<'info, SuperNode>,
+
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        seeds = [b"super_node", super_node.owner.as_ref()],
+        bump
+    )]
+    pub super_node: Account<'info, SuperNode>,
+
+    #[account(
+        seeds = [b"user", super_node.owner.as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + std::mem::size_of::<NodeUptime>(),
+        seeds = [b"uptime", super_node.owner.as_ref()],
+        bump
+    )]
+    pub node_uptime: Account<'info, NodeUptime>,
+
+    // 由观察者代为上报时提供
+    #[account(
+        seeds = [b"watcher", reporter.key().as_ref()],
+        bump
+    )]
+    pub watcher: Option<Account<'info, Watcher>>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DeregisterSuperNode<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = owner,
+        seeds = [b"super_node", owner.key().as_ref()],
+        bump
+    )]
+    pub super_node: Account<'info, SuperNode>,
+
+    /// CHECK: 节点所有者的用户账户, 可能已关闭, 由 seeds 约束校验
+    #[account(
+        seeds = [b"user", owner.key().as_ref()],
+        bump
+    )]
+    pub user_account: UncheckedAccount<'info>,
+
+    /// CHECK: 接收关闭节点账户返还的租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    pub caller: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u64)]
+pub struct CreateAuction<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", downloader.key().as_ref()],
+        bump
+    )]
+    pub downloader_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = downloader,
+        space = 8 + std::mem::size_of::<BandwidthAuction>(),
+        seeds = [b"auction", downloader.key().as_ref(), auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(mut)]
+    pub downloader: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitBid<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction.downloader.as_ref(), auction.auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"user", node.key().as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, UserAccount>,
+
+    // 已有其他节点报价时提供
+    #[account(
+        mut,
+        seeds = [b"user", auction.winner.as_ref()],
+        bump
+    )]
+    pub previous_winner_account: Option<Account<'info, UserAccount>>,
+
+    pub node: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBidding<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = downloader,
+        seeds = [b"auction", auction.downloader.as_ref(), auction.auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.downloader.as_ref()],
+        bump
+    )]
+    pub downloader_account: Account<'info, UserAccount>,
+
+    /// CHECK: 拍卖关闭时接收租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub downloader: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ConfirmDelivery<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = downloader,
+        has_one = downloader,
+        seeds = [b"auction", auction.downloader.as_ref(), auction.auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.winner.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.downloader.as_ref()],
+        bump
+    )]
+    pub downloader_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    pub downloader: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimDelivery<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = downloader,
+        has_one = downloader,
+        seeds = [b"auction", auction.downloader.as_ref(), auction.auction_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = auction.winner == winner.key() @ ErrorCode::Unauthorized
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"user", winner.key().as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.downloader.as_ref()],
+        bump
+    )]
+    pub downloader_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"attestor", attestor.key.as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    /// CHECK: 拍卖关闭时接收租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub downloader: UncheckedAccount<'info>,
+
+    pub winner: Signer<'info>,
+
+    /// CHECK: 指令 sysvar, 由 address 约束校验
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RefundAuction<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = downloader,
+        has_one = downloader,
+        seeds = [b"auction", auction.downloader.as_ref(), auction.auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, BandwidthAuction>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.downloader.as_ref()],
+        bump
+    )]
+    pub downloader_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", auction.winner.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, UserAccount>,
+
+    /// CHECK: 拍卖关闭时接收租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub downloader: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(channel_id: u64)]
+pub struct OpenChannel<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", payer.key().as_ref()],
+        bump
+    )]
+    pub payer_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", payee_account.owner.as_ref()],
+        bump,
+        constraint = payee_account.key() != payer_account.key() @ ErrorCode::InvalidChannel
+    )]
+    pub payee_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<PaymentChannel>(),
+        seeds = [b"channel", payer.key().as_ref(), payee_account.owner.as_ref(), channel_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub channel: Account<'info, PaymentChannel>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateChannel<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"channel", channel.payer.as_ref(), channel.payee.as_ref(), channel.channel_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub channel: Account<'info, PaymentChannel>,
+
+    pub signer: Signer<'info>,
+
+    /// CHECK: 指令 sysvar, 由 address 约束校验
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SettleChannel<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [b"channel", channel.payer.as_ref(), channel.payee.as_ref(), channel.channel_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub channel: Account<'info, PaymentChannel>,
+
+    #[account(
+        mut,
+        seeds = [b"user", channel.payer.as_ref()],
+        bump
+    )]
+    pub payer_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", channel.payee.as_ref()],
+        bump
+    )]
+    pub payee_account: Account<'info, UserAccount>,
+
+    /// CHECK: 通道关闭时接收租金, 由 has_one 约束校验
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + std::mem::size_of::<Unbonding>(),
+        seeds = [b"unbonding", user.key().as_ref()],
+        bump
+    )]
+    pub unbonding: Account<'info, Unbonding>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUnbonding<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"unbonding", user.key().as_ref()],
+        bump
+    )]
+    pub unbonding: Account<'info, Unbonding>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(evidence_hash: [u8; 32])]
+pub struct SlashNode<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        seeds = [b"watcher", reporter.key().as_ref()],
+        bump
+    )]
+    pub watcher: Account<'info, Watcher>,
+
+    #[account(
+        mut,
+        seeds = [b"user", node_account.owner.as_ref()],
+        bump
+    )]
+    pub node_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"unbonding", node_account.owner.as_ref()],
+        bump
+    )]
+    pub node_unbonding: Option<Account<'info, Unbonding>>,
+
+    // 首个提交证据的监控者的账户
+    #[account(
+        mut,
+        seeds = [b"user", reporter_account.owner.as_ref()],
+        bump
+    )]
+    pub reporter_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + std::mem::size_of::<SlashRecord>(),
+        seeds = [b"slash", node_account.key().as_ref(), evidence_hash.as_ref()],
+        bump
+    )]
+    pub slash_record: Account<'info, SlashRecord>,
+
+    #[account(
+        init,
+        payer = reporter,
+        space = 8 + std::mem::size_of::<SlashVote>(),
+        seeds = [b"slash_vote", slash_record.key().as_ref(), reporter.key().as_ref()],
+        bump
+    )]
+    pub slash_vote: Account<'info, SlashVote>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kind: FraudKind, evidence_hash: [u8; 32])]
+pub struct ReportFraud<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", target_account.owner.as_ref()],
+        bump
+    )]
+    pub target_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user", reporter.key().as_ref()],
+        bump
+    )]
+    pub reporter_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = reporter,
+        space = 8 + std::mem::size_of::<FraudReport>(),
+        seeds = [b"fraud", target_account.key().as_ref(), evidence_hash.as_ref()],
+        bump
+    )]
+    pub fraud_report: Account<'info, FraudReport>,
+
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FraudResolution<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"fraud", fraud_report.target.as_ref(), fraud_report.evidence_hash.as_ref()],
+        bump
+    )]
+    pub fraud_report: Account<'info, FraudReport>,
+
+    #[account(
+        mut,
+        address = fraud_report.target
+    )]
+    pub target_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"unbonding", target_account.owner.as_ref()],
+        bump
+    )]
+    pub target_unbonding: Option<Account<'info, Unbonding>>,
+
+    #[account(
+        mut,
+        seeds = [b"user", fraud_report.reporter.as_ref()],
+        bump
+    )]
+    pub reporter_account: Account<'info, UserAccount>,
+}
+
+impl<'info> FraudResolution<'info> {
+    /// 按裁决结果结算举报, 罚没中未奖励给举报者的部分和被驳回举报的保证金归入奖励池
+    pub fn resolve(&mut self, upheld: bool, resolved_by: Pubkey) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let fraud_report = &mut self.fraud_report;
+        let (penalty, bounty) = fraud_report.settle(
+            upheld,
+            &self.reward_config.params,
+            &mut self.target_account,
+            self.target_unbonding.as_deref_mut(),
+            &mut self.reporter_account,
+            now,
+        )?;
+
+        let to_pool = if upheld { penalty - bounty } else { fraud_report.bond };
+        let reward_pool = &mut self.reward_pool;
+        reward_pool.total_rewards = reward_pool.total_rewards
+            .checked_add(to_pool)
+            .ok_or(ErrorCode::MathOverflow)?;
+        fraud_report.resolved_by = resolved_by;
+        fraud_report.resolved_at = now;
+
+        emit!(FraudResolvedEvent {
+            report: fraud_report.key(),
+            target: fraud_report.target,
+            reporter: fraud_report.reporter,
+            kind: fraud_report.kind,
+            upheld,
+            penalty,
+            bounty,
+            banned: self.target_account.banned,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct AdjudicateFraud<'info> {
+    pub resolution: FraudResolution<'info>,
+
+    /// CHECK: 由 authorize 校验的管理员密钥或多签账户
+    pub adjudicator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireFraudReport<'info> {
+    pub resolution: FraudResolution<'info>,
+}
+
+#[derive(Accounts)]
+pub struct VoteFraudReport<'info> {
+    pub resolution: FraudResolution<'info>,
+
+    #[account(
+        seeds = [b"attestor", voter.key().as_ref()],
+        bump
+    )]
+    pub attestor: Account<'info, Attestor>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + std::mem::size_of::<FraudVote>(),
+        seeds = [b"fraud_vote", resolution.fraud_report.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub fraud_vote: Account<'info, FraudVote>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = vault
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: 关闭记录地址, 必须不存在 (已关闭的钱包不能重新创建账户)
+    #[account(
+        seeds = [b"closed_user", user.key().as_ref()],
+        bump
+    )]
+    pub closed_user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.mint,
+        token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = vault
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseUserAccount<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump,
+        has_one = vault
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"user", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: 解绑记录地址, 必须不存在 (无进行中的解绑)
+    #[account(
+        seeds = [b"unbonding", user.key().as_ref()],
+        bump
+    )]
+    pub unbonding: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + ClosedUser::INIT_SPACE,
+        seeds = [b"closed_user", user.key().as_ref()],
+        bump
+    )]
+    pub closed_user: Account<'info, ClosedUser>,
+
+    #[account(
+        mut,
+        token::mint = reward_pool.mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"user", proposer.key().as_ref()],
+        bump
+    )]
+    pub proposer_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + Proposal::INIT_SPACE,
+        seeds = [b"proposal", reward_pool.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    // 无当前纪元时可不传, 此时投票照常计入但不发放奖励
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_epoch.index.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub reward_epoch: Option<Account<'info, RewardEpoch>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"user", voter.key().as_ref()],
+        bump
+    )]
+    pub voter_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = 8 + std::mem::size_of::<VoteRecord>(),
+        seeds = [b"vote", proposal.key().as_ref(), voter_account.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotes<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"user", delegator.key().as_ref()],
+        bump
+    )]
+    pub delegator_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"user", delegate_account.owner.as_ref()],
+        bump,
+        constraint = delegate_account.key() != delegator_account.key() @ ErrorCode::InvalidDelegation
+    )]
+    pub delegate_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = delegator,
+        space = 8 + std::mem::size_of::<Delegation>(),
+        seeds = [b"delegation", delegator_account.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"user", delegator.key().as_ref()],
+        bump
+    )]
+    pub delegator_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        close = delegator,
+        seeds = [b"delegation", delegator_account.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CastDelegatedVote<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"user", delegator_account.owner.as_ref()],
+        bump
+    )]
+    pub delegator_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"delegation", delegator_account.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(
+        mut,
+        address = delegation.delegate
+    )]
+    pub delegate_account: Account<'info, UserAccount>,
+
+    /// 受托人本人的投票记录, 只跟随直接投票, 不支持多级委托
+    #[account(
+        mut,
+        seeds = [b"vote", proposal.key().as_ref(), delegation.delegate.as_ref()],
+        bump,
+        constraint = delegate_vote_record.delegate == Pubkey::default() @ ErrorCode::InvalidDelegation
+    )]
+    pub delegate_vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<VoteRecord>(),
+        seeds = [b"vote", proposal.key().as_ref(), delegator_account.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump
+    )]
+    pub reward_config: Account<'info, RewardConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct SettleProposalBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"user", proposal.proposer.as_ref()],
+        bump
+    )]
+    pub proposer_account: Account<'info, UserAccount>,
+}
+
+// Event definitions
+
+#[event]
+pub struct UploadRewardEvent {
+    pub user: Pubkey,
+    pub attestor: Pubkey,
+    pub amount: u64,
+    pub bytes: u64,
+    pub info_hash: [u8; 32],
+    pub rarity_multiplier: u64,
+    pub nonce: u64,
+}
+
+#[event]
+pub struct DepositEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct WithdrawEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct ParamsUpdated {
+    pub authority: Pubkey,
+    pub params: RewardParams,
+}
+
+#[event]
+pub struct PoolFundedEvent {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_rewards: u64,       // Total TF funded via fund_pool
+}
+
+#[event]
+pub struct AttestorRegisteredEvent {
+    pub attestor: Pubkey,
+}
+
+#[event]
+pub struct AttestorRemovedEvent {
+    pub attestor: Pubkey,
+}
+
+#[event]
+pub struct NodeRewardEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub window_hours: u64,
+    pub online_hours: u64,
+    pub uptime_percentage: u8,
+}
+
+#[event]
+pub struct SeedRewardEvent {
+    pub user: Pubkey,
+    pub info_hash: [u8; 32],
+    pub amount: u64,
+    pub duration_hours: u64,
+    pub file_popularity: u8,
+}
+
+#[event]
+pub struct SpeedBoostEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+    pub tier: u8,
+    pub expires_at_slot: u64,
+}
+
+#[event]
+pub struct StakeEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct UnstakeEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub remaining_staked: u64,
+    pub pending_amount: u64,
+    pub unlock_at: i64,
+}
+
+#[event]
+pub struct EmissionStartedEvent {
+    pub start: i64,
+    pub total_emission: u64,
+    pub epochs: u64,
+    pub epoch_duration: i64,
+}
+
+#[event]
+pub struct EpochOpenedEvent {
+    pub index: u64,
+    pub start: i64,
+    pub end: i64,
+    pub budget: u64,
+}
+
+#[event]
+pub struct UnstakeCompletedEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub new_balance: u64,
+}
+
+#[event]
+pub struct UnstakeCancelledEvent {
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct WatcherAddedEvent {
+    pub watcher: Pubkey,
+}
+
+#[event]
+pub struct WatcherRemovedEvent {
+    pub watcher: Pubkey,
+}
+
+#[event]
+pub struct SlashApprovedEvent {
+    pub node: Pubkey,
+    pub watcher: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub approvals: u8,
+}
+
+#[event]
+pub struct SlashEvent {
+    pub node: Pubkey,
+    pub reporter: Pubkey,
+    pub evidence_hash: [u8; 32],
+    pub amount: u64,
+    pub reporter_reward: u64,
+    pub remaining_staked: u64,
+}
+
+#[event]
+pub struct ContentRegisteredEvent {
+    pub info_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub piece_count: u32,
+    pub size_bytes: u64,
+    pub attestor: Pubkey,
+}
+
+#[event]
+pub struct ContentStatsEvent {
+    pub info_hash: [u8; 32],
+    pub active_seeders: u32,
+    pub download_count: u64,
+}
+
+#[event]
+pub struct ChallengeIssuedEvent {
+    pub challenger: Pubkey,
+    pub target: Pubkey,
+    pub info_hash: [u8; 32],
+    pub piece_index: u32,
+    pub deadline_slot: u64,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ChallengePassedEvent {
+    pub target: Pubkey,
+    pub info_hash: [u8; 32],
+    pub piece_index: u32,
+}
+
+#[event]
+pub struct ChallengeFailedEvent {
+    pub target: Pubkey,
+    pub info_hash: [u8; 32],
+    pub piece_index: u32,
+}
+
+#[event]
+pub struct UserRegisteredEvent {
+    pub user: Pubkey,
+    pub owner: Pubkey,
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ReferralRewardEvent {
+    pub referrer: Pubkey,
+    pub invitee: Pubkey,
+    pub amount: u64,
+    pub referrals_paid: u32,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub kind: ProposalKind,
+    pub voting_ends_at: i64,
+    pub executable_at: i64,
+    pub expires_at: i64,
+    pub quorum: u64,
+}
+
+#[event]
+pub struct VoteCastEvent {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub delegate: Pubkey,
+    pub approve: bool,
+    pub weight: u64,
+    pub reward: u64,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal: Pubkey,
+    pub id: u64,
+    pub kind: ProposalKind,
+}
+
+#[event]
+pub struct ProposalBondSettledEvent {
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub bond: u64,
+    pub refunded: bool,
+}
+
+#[event]
+pub struct DelegationEvent {
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub active: bool,
+}
+
+#[event]
+pub struct SubscriptionEvent {
+    pub user: Pubkey,
+    pub renewed_by: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub auto_renew_allowance: u64,
+}
+
+#[event]
+pub struct SubscriptionCancelledEvent {
+    pub user: Pubkey,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct AuctionCreatedEvent {
+    pub auction: Pubkey,
+    pub downloader: Pubkey,
+    pub info_hash: [u8; 32],
+    pub size_gb: u64,
+    pub max_price_per_gb: u64,
+    pub bid_deadline: i64,
+}
+
+#[event]
+pub struct BidSubmittedEvent {
+    pub auction: Pubkey,
+    pub node: Pubkey,
+    pub price_per_gb: u64,
+}
+
+#[event]
+pub struct AuctionAwardedEvent {
+    pub auction: Pubkey,
+    pub winner: Pubkey,
+    pub price_per_gb: u64,
+    pub escrow: u64,
+    pub delivery_deadline: i64,
+}
+
+#[event]
+pub struct AuctionSettledEvent {
+    pub auction: Pubkey,
+    pub winner: Pubkey,
+    pub paid: u64,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct ChannelOpenedEvent {
+    pub channel: Pubkey,
+    pub payer: Pubkey,
+    pub payee: Pubkey,
+    pub channel_id: u64,
+    pub deposit: u64,
+}
+
+#[event]
+pub struct ChannelClosingEvent {
+    pub channel: Pubkey,
+    pub closed_by: Pubkey,
+    pub claimed_amount: u64,
+    pub dispute_ends_at: i64,
+}
+
+#[event]
+pub struct ChannelDisputedEvent {
+    pub channel: Pubkey,
+    pub claimed_amount: u64,
+}
+
+#[event]
+pub struct ChannelSettledEvent {
+    pub channel: Pubkey,
+    pub paid: u64,
+    pub refunded: u64,
 }
 
 #[event]
-pub struct UnstakeEvent {
+pub struct SuperNodeRegisteredEvent {
+    pub owner: Pubkey,
+    pub peer_id: String,
+    pub bandwidth_tier: BandwidthTier,
+    pub region: String,
+}
+
+#[event]
+pub struct SuperNodeUpdatedEvent {
+    pub owner: Pubkey,
+    pub peer_id: String,
+    pub bandwidth_tier: BandwidthTier,
+    pub region: String,
+}
+
+#[event]
+pub struct SuperNodeDeregisteredEvent {
+    pub owner: Pubkey,
+    pub caller: Pubkey,           // Differs from owner when removed for low stake
+}
+
+#[event]
+pub struct HeartbeatEvent {
+    pub owner: Pubkey,
+    pub hour: u64,
+    pub reporter: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdatedEvent {
+    pub paused: u8,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct GuardianUpdatedEvent {
+    pub guardian: Pubkey,
+}
+
+#[event]
+pub struct AuthorityProposedEvent {
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferredEvent {
+    pub previous: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct MultisigUpdatedEvent {
+    pub multisig: Pubkey,
+    pub signers: Vec<Pubkey>,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct RewardPoolMigratedEvent {
+    pub reward_pool: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct UserAccountMigratedEvent {
     pub user: Pubkey,
-    pub amount: u64,
-    pub remaining_staked: u64,
+    pub version: u8,
+}
+
+#[event]
+pub struct UserAccountClosedEvent {
+    pub user: Pubkey,
+    pub withdrawn: u64,
+}
+
+#[event]
+pub struct FraudVoteEvent {
+    pub report: Pubkey,
+    pub attestor: Pubkey,
+    pub upheld: bool,
+    pub uphold_votes: u8,
+    pub reject_votes: u8,
+}
+
+#[event]
+pub struct FraudReportedEvent {
+    pub report: Pubkey,
+    pub reporter: Pubkey,
+    pub target: Pubkey,
+    pub kind: FraudKind,
+    pub evidence_hash: [u8; 32],
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct FraudResolvedEvent {
+    pub report: Pubkey,
+    pub target: Pubkey,
+    pub reporter: Pubkey,
+    pub kind: FraudKind,
+    pub upheld: bool,
+    pub penalty: u64,
+    pub bounty: u64,
+    pub banned: bool,
 }
 
 // Error definitions
@@ -477,4 +6321,848 @@ pub enum ErrorCode {
     
     #[msg("Node uptime too low")]
     LowUptime,
+
+    #[msg("Signer is not the program authority")]
+    Unauthorized,
+
+    #[msg("Expected an ed25519 signature instruction before this instruction")]
+    MissingSignatureInstruction,
+
+    #[msg("Ed25519 signature instruction does not match the expected signer or message")]
+    InvalidSignatureInstruction,
+
+    #[msg("Upload receipt was issued for a different user")]
+    ReceiptUserMismatch,
+
+    #[msg("Upload receipt nonce has already been used")]
+    ReceiptNonceUsed,
+
+    // 已弃用: 稀缺系数改为取自内容登记表, 保留该变体以免后续错误码编号变化
+    #[msg("Rarity multiplier out of range")]
+    InvalidRarityMultiplier,
+
+    #[msg("Reward pool budget exhausted")]
+    RewardPoolExhausted,
+
+    #[msg("Invalid reward parameters")]
+    InvalidParams,
+
+    #[msg("Emission has already been started")]
+    EmissionAlreadyStarted,
+
+    #[msg("Emission has not been started")]
+    EmissionNotStarted,
+
+    #[msg("Emission schedule has ended")]
+    EmissionEnded,
+
+    #[msg("Reward epoch is not the current epoch")]
+    EpochNotActive,
+
+    #[msg("Reward epoch budget exhausted")]
+    EpochBudgetExhausted,
+
+    #[msg("Unbonding cooldown has not elapsed")]
+    UnbondingNotComplete,
+
+    #[msg("Stake was slashed while unbonding")]
+    SlashedDuringUnbonding,
+
+    #[msg("Node has no stake to slash")]
+    NothingToSlash,
+
+    #[msg("Invalid content registration")]
+    InvalidContent,
+
+    #[msg("Invalid possession challenge")]
+    InvalidChallenge,
+
+    #[msg("Challenge response deadline has passed")]
+    ChallengeExpired,
+
+    #[msg("Challenge response deadline has not passed yet")]
+    ChallengeNotExpired,
+
+    #[msg("Merkle proof does not match the registered content root")]
+    InvalidMerkleProof,
+
+    #[msg("Invalid or missing referrer")]
+    InvalidReferrer,
+
+    #[msg("Referral reward has already been paid")]
+    ReferralAlreadyPaid,
+
+    #[msg("Invitee has not reached the referral activity threshold")]
+    ReferralNotActive,
+
+    #[msg("Referrer has reached the referral reward cap")]
+    ReferralCapReached,
+
+    #[msg("Voting weight too low")]
+    InsufficientVotingWeight,
+
+    #[msg("Invalid proposal")]
+    InvalidProposal,
+
+    #[msg("Voting period has ended")]
+    VotingClosed,
+
+    #[msg("Proposal timelock has not elapsed")]
+    TimelockNotElapsed,
+
+    #[msg("Proposal did not reach its quorum or approval threshold")]
+    ProposalNotPassed,
+
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Balance is locked by an active governance vote")]
+    GovernanceLocked,
+
+    #[msg("Invalid vote delegation")]
+    InvalidDelegation,
+
+    #[msg("Speed tier is not configured")]
+    InvalidSpeedTier,
+
+    #[msg("An active speed boost can only be extended at the same tier")]
+    SpeedTierMismatch,
+
+    #[msg("Subscription is not due for renewal")]
+    SubscriptionNotDue,
+
+    #[msg("Auto-renew allowance exhausted")]
+    AllowanceExhausted,
+
+    #[msg("Invalid bandwidth auction")]
+    InvalidAuction,
+
+    #[msg("Invalid bid")]
+    InvalidBid,
+
+    #[msg("Bid must be lower than the current best bid")]
+    BidNotLowest,
+
+    #[msg("Bidding period has ended")]
+    BiddingClosed,
+
+    #[msg("Bidding period has not ended")]
+    BiddingOpen,
+
+    #[msg("Delivery deadline has not passed")]
+    DeliveryNotExpired,
+
+    #[msg("Invalid payment channel")]
+    InvalidChannel,
+
+    #[msg("Invalid payment channel voucher")]
+    InvalidVoucher,
+
+    #[msg("Payment channel is already closing")]
+    ChannelClosing,
+
+    #[msg("Payment channel is not closing")]
+    ChannelNotClosing,
+
+    #[msg("Channel dispute period has ended")]
+    DisputePeriodEnded,
+
+    #[msg("Channel dispute period is still active")]
+    DisputePeriodActive,
+
+    #[msg("Invalid super node peer id, multiaddrs or region")]
+    InvalidNodeInfo,
+
+    #[msg("Uptime claim window is too short")]
+    UptimeWindowTooShort,
+
+    #[msg("This service period has already been claimed")]
+    ClaimAlreadyProcessed,
+
+    #[msg("Program is paused")]
+    ProgramPaused,
+
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+
+    #[msg("Invalid multisig signers or threshold")]
+    InvalidMultisig,
+
+    #[msg("Not enough multisig signers")]
+    NotEnoughSigners,
+
+    #[msg("Account layout is not a known version")]
+    InvalidAccountVersion,
+
+    #[msg("Account is already at the current version")]
+    AccountAlreadyMigrated,
+
+    #[msg("Account still has stake or a pending unbonding")]
+    StakeOutstanding,
+
+    #[msg("Account is banned")]
+    AccountBanned,
+
+    #[msg("Fraud report has already been resolved")]
+    FraudReportResolved,
+
+    #[msg("Delivery receipt does not match the auction")]
+    InvalidDeliveryReceipt,
+
+    #[msg("Account still has open channels, auctions, challenges or reports")]
+    OpenEscrows,
+
+    #[msg("This wallet closed its account and cannot create a new one")]
+    AccountClosed,
+
+    #[msg("Account is frozen while a fraud report against it is pending")]
+    AccountFrozen,
+
+    #[msg("This evidence has already been used to slash")]
+    SlashExecuted,
+
+    #[msg("Proposal execution window has passed")]
+    ProposalExpired,
+
+    #[msg("Reward parameters changed after the proposal was created")]
+    ProposalConfigChanged,
+
+    #[msg("Voting period has not ended")]
+    VotingNotEnded,
+
+    #[msg("Proposal bond has already been settled")]
+    ProposalBondSettled,
+
+    #[msg("Account still has open seeder records")]
+    ActiveSeedings,
+
+    #[msg("Fraud report has not expired yet")]
+    FraudReportNotExpired,
+
+    #[msg("Reporter has too many pending fraud reports")]
+    TooManyOpenReports,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 按 ed25519 程序布局构造单签名指令: 头部 16 字节, 随后为公钥、签名和消息
+    fn ed25519_ix(pubkey: &Pubkey, message: &[u8]) -> Instruction {
+        let pubkey_offset = 16u16;
+        let signature_offset = pubkey_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1u8, 0];
+        for value in [
+            signature_offset,
+            u16::MAX,
+            pubkey_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(pubkey.as_ref());
+        data.extend_from_slice(&[7u8; 64]);
+        data.extend_from_slice(message);
+
+        Instruction {
+            program_id: ed25519_program::ID,
+            accounts: vec![],
+            data,
+        }
+    }
+
+    fn set_u16(ix: &mut Instruction, at: usize, value: u16) {
+        ix.data[at..at + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn proposal(kind: ProposalKind, yes_weight: u64, no_weight: u64, quorum: u64) -> Proposal {
+        Proposal {
+            id: 0,
+            proposer: Pubkey::default(),
+            kind,
+            params: None,
+            description_hash: [0u8; 32],
+            created_at: 0,
+            voting_ends_at: 0,
+            executable_at: 0,
+            expires_at: 0,
+            config_updated_at: 0,
+            bond: 0,
+            bond_settled: false,
+            yes_weight,
+            no_weight,
+            executed: false,
+            quorum,
+            version: PROPOSAL_VERSION,
+            reserved: [0; ACCOUNT_RESERVED_BYTES - 25],
+        }
+    }
+
+    #[test]
+    fn init_space_fits_largest_serialized_layout() {
+        let config = RewardConfig {
+            params: RewardParams::default(),
+            updated_at: 0,
+            version: REWARD_CONFIG_VERSION,
+            reserved: [0; ACCOUNT_RESERVED_BYTES],
+        };
+        assert_eq!(config.try_to_vec().unwrap().len(), RewardConfig::INIT_SPACE);
+
+        let mut with_params = proposal(ProposalKind::EconomicParams, 0, 0, 0);
+        with_params.params = Some(RewardParams::default());
+        assert_eq!(with_params.try_to_vec().unwrap().len(), Proposal::INIT_SPACE);
+    }
+
+    #[test]
+    fn proposal_requires_quorum_and_threshold() {
+        let kind = ProposalKind::EconomicParams;
+        assert!(proposal(kind, 60, 40, 100).passed());
+        assert!(!proposal(kind, 60, 39, 100).passed());
+        assert!(!proposal(kind, 50, 50, 100).passed());
+        assert!(!proposal(kind, 0, 0, 0).passed());
+        assert!(!proposal(ProposalKind::ProtocolUpgrade, 60, 40, 100).passed());
+    }
+
+    #[test]
+    fn proposal_executes_only_within_window_and_on_unchanged_config() {
+        let mut passed = proposal(ProposalKind::EconomicParams, 60, 40, 100);
+        passed.params = Some(RewardParams::default());
+        passed.executable_at = 1_000;
+        passed.expires_at = 1_000 + PROPOSAL_EXECUTION_WINDOW;
+        passed.config_updated_at = 500;
+
+        assert_eq!(
+            passed.check_executable(999, 500).unwrap_err(),
+            ErrorCode::TimelockNotElapsed.into()
+        );
+        assert!(passed.check_executable(1_000, 500).is_ok());
+        assert_eq!(
+            passed.check_executable(passed.expires_at, 500).unwrap_err(),
+            ErrorCode::ProposalExpired.into()
+        );
+        assert_eq!(
+            passed.check_executable(1_000, 600).unwrap_err(),
+            ErrorCode::ProposalConfigChanged.into()
+        );
+
+        // 协议升级提案不写入配置, 不受配置变更影响
+        let mut upgrade = proposal(ProposalKind::ProtocolUpgrade, 70, 30, 100);
+        upgrade.expires_at = PROPOSAL_EXECUTION_WINDOW;
+        assert!(upgrade.check_executable(0, 600).is_ok());
+    }
+
+    fn user_account() -> UserAccount {
+        UserAccount::deserialize(&mut &[0u8; UserAccount::INIT_SPACE][..]).unwrap()
+    }
+
+    #[test]
+    fn forfeit_takes_balance_then_stake_then_unbonding() {
+        let mut user = user_account();
+        user.balance = 30;
+        user.staked_amount = 50;
+        let mut unbonding = Unbonding {
+            owner: user.owner,
+            amount: 40,
+            unlock_at: 0,
+            slash_count: 0,
+        };
+
+        assert_eq!(user.forfeit(60, Some(&mut unbonding)), 60);
+        assert_eq!((user.balance, user.staked_amount, unbonding.amount), (0, 20, 40));
+
+        assert_eq!(user.forfeit(u64::MAX, Some(&mut unbonding)), 60);
+        assert_eq!((user.staked_amount, unbonding.amount), (0, 0));
+        assert_eq!(user.forfeit(10, None), 0);
+    }
+
+    fn fraud_report(kind: FraudKind, bond: u64) -> FraudReport {
+        FraudReport {
+            reporter: Pubkey::new_unique(),
+            target: Pubkey::new_unique(),
+            kind,
+            evidence_hash: [0; 32],
+            bond,
+            status: FraudStatus::Pending,
+            reported_at: 0,
+            resolved_by: Pubkey::default(),
+            resolved_at: 0,
+            uphold_votes: 0,
+            reject_votes: 0,
+            expires_at: 0,
+        }
+    }
+
+    /// 举报提交后的双方账户: 各占一个托管, 被举报账户处于冻结状态
+    fn fraud_parties() -> (UserAccount, UserAccount) {
+        let mut target = user_account();
+        target.open_escrow().unwrap();
+        target.pending_reports = 1;
+        target.reputation_score = 500;
+        let mut reporter = user_account();
+        reporter.open_escrow().unwrap();
+        reporter.open_report(1).unwrap();
+        (target, reporter)
+    }
+
+    fn assert_released(target: &UserAccount, reporter: &UserAccount) {
+        assert_eq!((target.pending_reports, target.open_escrows), (0, 0));
+        assert_eq!((reporter.open_reports, reporter.open_escrows), (0, 0));
+        target.require_not_frozen().unwrap();
+    }
+
+    #[test]
+    fn upheld_fake_upload_pays_reporter_share_of_penalty() {
+        let params = RewardParams {
+            fake_upload_penalty: 100,
+            fake_upload_reporter_bps: 5_000,
+            ..RewardParams::default()
+        };
+        let mut report = fraud_report(FraudKind::FakeUpload, 10);
+        let (mut target, mut reporter) = fraud_parties();
+        target.balance = 30;
+        target.staked_amount = 200;
+
+        let settled = report.settle(true, &params, &mut target, None, &mut reporter, 1_000).unwrap();
+        assert_eq!(settled, (100, 50));
+        assert_eq!((target.balance, target.staked_amount), (0, 130));
+        assert_eq!(reporter.balance, 10 + 50);
+        assert_eq!(target.reputation_score, 500 - REPUTATION_SLASH_PENALTY as u32);
+        assert!(!target.banned);
+        assert!(report.status == FraudStatus::Upheld);
+        assert_released(&target, &reporter);
+    }
+
+    #[test]
+    fn upheld_spam_bans_and_forfeits_everything() {
+        let params = RewardParams {
+            spam_reporter_bounty: 100,
+            ..RewardParams::default()
+        };
+        let mut report = fraud_report(FraudKind::Spam, 10);
+        let (mut target, mut reporter) = fraud_parties();
+        target.balance = 30;
+        target.staked_amount = 20;
+        let mut unbonding = Unbonding {
+            owner: target.owner,
+            amount: 40,
+            unlock_at: 0,
+            slash_count: 0,
+        };
+
+        let settled = report
+            .settle(true, &params, &mut target, Some(&mut unbonding), &mut reporter, 1_000)
+            .unwrap();
+        // 奖励不超过实际罚没数量
+        assert_eq!(settled, (90, 90));
+        assert_eq!((target.balance, target.staked_amount, unbonding.amount), (0, 0, 0));
+        assert_eq!(reporter.balance, 10 + 90);
+        assert_eq!(
+            target.require_not_banned().unwrap_err(),
+            ErrorCode::AccountBanned.into()
+        );
+        assert_released(&target, &reporter);
+    }
+
+    #[test]
+    fn rejected_report_keeps_bond_and_settles_once() {
+        let params = RewardParams::default();
+        let mut report = fraud_report(FraudKind::FakeUpload, 10);
+        let (mut target, mut reporter) = fraud_parties();
+        target.balance = 30;
+
+        let settled = report.settle(false, &params, &mut target, None, &mut reporter, 1_000).unwrap();
+        assert_eq!(settled, (0, 0));
+        assert_eq!((target.balance, reporter.balance), (30, 0));
+        assert_eq!(target.reputation_score, 500);
+        assert!(report.status == FraudStatus::Rejected);
+        assert_released(&target, &reporter);
+
+        assert_eq!(
+            report
+                .settle(true, &params, &mut target, None, &mut reporter, 1_000)
+                .unwrap_err(),
+            ErrorCode::FraudReportResolved.into()
+        );
+    }
+
+    #[test]
+    fn open_reports_are_capped_per_reporter() {
+        let mut reporter = user_account();
+        reporter.open_report(2).unwrap();
+        reporter.open_report(2).unwrap();
+        assert_eq!(
+            reporter.open_report(2).unwrap_err(),
+            ErrorCode::TooManyOpenReports.into()
+        );
+
+        reporter.close_report();
+        reporter.open_report(2).unwrap();
+        assert_eq!(reporter.open_reports, 2);
+    }
+
+    /// 按基线程序的分配方式 (8 + size_of) 构造账户数据, 字段之后为零填充
+    fn baseline_account(discriminator: [u8; 8], fields: &[&[u8]], size_of: usize) -> Vec<u8> {
+        let mut data = discriminator.to_vec();
+        for field in fields {
+            data.extend_from_slice(field);
+        }
+        assert!(data.len() <= 8 + size_of);
+        data.resize(8 + size_of, 0);
+        data
+    }
+
+    #[test]
+    fn legacy_user_account_upgrades_from_baseline_bytes() {
+        let owner = Pubkey::new_unique();
+        // 基线 UserAccount: Pubkey + 6 x u64 + u32, size_of 对齐到 88 字节
+        let data = baseline_account(
+            UserAccount::DISCRIMINATOR,
+            &[
+                owner.as_ref(),
+                &7u64.to_le_bytes(),
+                &8u64.to_le_bytes(),
+                &3u64.to_le_bytes(),
+                &9u64.to_le_bytes(),
+                &10u64.to_le_bytes(),
+                &11u64.to_le_bytes(),
+                &900u32.to_le_bytes(),
+            ],
+            88,
+        );
+
+        let upgraded = LegacyUserAccount::deserialize(&mut &data[8..]).unwrap().upgrade();
+        let mut migrated = vec![0u8; 8 + UserAccount::INIT_SPACE];
+        upgraded.try_serialize(&mut &mut migrated[..]).unwrap();
+        let user = UserAccount::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(user.owner, owner);
+        assert_eq!(user.balance, 7);
+        assert_eq!(user.staked_amount, 8);
+        assert_eq!(user.total_uploaded, 3 * BYTES_PER_GB);
+        assert_eq!(user.total_consumed, 9);
+        assert_eq!(user.seeding_hours, 10);
+        assert_eq!(user.node_operation_hours, 11);
+        assert_eq!(user.reputation_score, 900);
+        assert_eq!(user.version, USER_ACCOUNT_VERSION);
+        assert_eq!(user.referrer, Pubkey::default());
+        assert_eq!(user.open_escrows, 0);
+    }
+
+    #[test]
+    fn legacy_reward_pool_upgrades_from_baseline_bytes() {
+        let authority = Pubkey::new_unique();
+        // 基线 RewardPool: Pubkey + 5 x u64
+        let data = baseline_account(
+            RewardPool::DISCRIMINATOR,
+            &[
+                authority.as_ref(),
+                &1_000u64.to_le_bytes(),
+                &400u64.to_le_bytes(),
+                &3u64.to_le_bytes(),
+                &4u64.to_le_bytes(),
+                &5u64.to_le_bytes(),
+            ],
+            72,
+        );
+
+        let legacy = LegacyRewardPool::deserialize(&mut &data[8..]).unwrap();
+        let (mint, vault) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut migrated = vec![0u8; 8 + RewardPool::INIT_SPACE];
+        legacy.upgrade(mint, vault, 254).try_serialize(&mut &mut migrated[..]).unwrap();
+        let pool = RewardPool::try_deserialize(&mut &migrated[..]).unwrap();
+
+        assert_eq!(pool.authority, authority);
+        assert_eq!((pool.mint, pool.vault, pool.bump), (mint, vault, 254));
+        assert_eq!(pool.total_rewards, 1_000);
+        assert_eq!(pool.total_distributed, 400);
+        assert_eq!(pool.version, REWARD_POOL_VERSION);
+
+        let params = legacy.params();
+        assert_eq!(
+            (params.upload_reward_rate, params.node_reward_rate, params.seed_reward_rate),
+            (3, 4, 5)
+        );
+        params.validate().unwrap();
+    }
+
+    #[test]
+    fn vote_rewards_are_capped_per_epoch() {
+        let mut voter = user_account();
+        assert!((0..3).all(|_| voter.take_vote_reward(5, 3)));
+        assert!(!voter.take_vote_reward(5, 3));
+        assert!(voter.take_vote_reward(6, 3));
+        assert_eq!(voter.vote_rewards_in_epoch, 1);
+        assert!(!voter.take_vote_reward(7, 0));
+    }
+
+    #[test]
+    fn delegator_shares_never_exceed_delegate_reward() {
+        let mut record = VoteRecord {
+            proposal: Pubkey::default(),
+            voter: Pubkey::default(),
+            approve: true,
+            weight: 0,
+            delegate: Pubkey::default(),
+            reward: 1_000,
+            shared: 0,
+        };
+        let shares: Vec<u64> = (0..12).map(|_| record.take_delegator_share(1_000)).collect();
+        assert_eq!(shares[0], 100);
+        assert_eq!(shares[9], 100);
+        assert_eq!(shares[10], 0);
+        assert_eq!(shares.iter().sum::<u64>(), record.reward);
+    }
+
+    #[test]
+    fn reputation_decays_per_whole_period() {
+        let mut user = user_account();
+        user.decay_reputation(1_000);
+        assert_eq!(user.reputation_updated_at, 1_000);
+
+        user.reputation_score = 100;
+        user.decay_reputation(1_000 + REPUTATION_DECAY_INTERVAL - 1);
+        assert_eq!(user.reputation_score, 100);
+
+        // 不足一个周期的余量保留到下次衰减
+        user.decay_reputation(1_000 + 2 * REPUTATION_DECAY_INTERVAL + 5);
+        assert_eq!(user.reputation_score, 100 - 2 * REPUTATION_DECAY_POINTS);
+        assert_eq!(user.reputation_updated_at, 1_000 + 2 * REPUTATION_DECAY_INTERVAL);
+
+        user.decay_reputation(1_000 + 100 * REPUTATION_DECAY_INTERVAL);
+        assert_eq!(user.reputation_score, 0);
+    }
+
+    #[test]
+    fn reputation_cap_scales_to_max_bonus() {
+        let mut user = user_account();
+        assert_eq!(user.reputation_multiplier_cap(), 100);
+        user.reputation_score = REPUTATION_MAX / 2;
+        assert_eq!(user.reputation_multiplier_cap(), 300);
+        user.reputation_score = REPUTATION_MAX;
+        assert_eq!(user.reputation_multiplier_cap(), MAX_BONUS_MULTIPLIER as u64);
+        user.reputation_score = REPUTATION_MAX * 2;
+        assert_eq!(user.reputation_multiplier_cap(), MAX_BONUS_MULTIPLIER as u64);
+    }
+
+    #[test]
+    fn service_reputation_bumps_once_per_interval() {
+        let mut user = user_account();
+        let start = REPUTATION_SERVICE_BUMP_INTERVAL;
+        user.bump_service_reputation(start);
+        for hour in 1..24 {
+            user.bump_service_reputation(start + hour * HEARTBEAT_INTERVAL);
+        }
+        assert_eq!(user.reputation_score, REPUTATION_SERVICE_BUMP as u32);
+
+        user.bump_service_reputation(start + REPUTATION_SERVICE_BUMP_INTERVAL);
+        assert_eq!(user.reputation_score, 2 * REPUTATION_SERVICE_BUMP as u32);
+    }
+
+    #[test]
+    fn referral_ignores_self_issued_challenges() {
+        let params = RewardParams::default();
+        let mut invitee = user_account();
+        for _ in 0..params.referral_challenge_threshold * 2 {
+            invitee.record_challenge_passed(false).unwrap();
+        }
+        assert!(!invitee.referral_active(&params));
+
+        for _ in 0..params.referral_challenge_threshold {
+            invitee.record_challenge_passed(true).unwrap();
+        }
+        assert!(invitee.referral_active(&params));
+        assert_eq!(invitee.challenges_passed, params.referral_challenge_threshold * 3);
+
+        let mut uploader = user_account();
+        uploader.total_uploaded = params.referral_upload_threshold;
+        assert!(uploader.referral_active(&params));
+    }
+
+    fn uptime_with(hours: impl IntoIterator<Item = u64>) -> NodeUptime {
+        let mut uptime = NodeUptime {
+            owner: Pubkey::default(),
+            heartbeats: [0u8; (UPTIME_WINDOW_HOURS / 8) as usize],
+            last_heartbeat_hour: 0,
+            claimed_until_hour: 0,
+        };
+        for hour in hours {
+            assert!(uptime.record(hour));
+        }
+        uptime
+    }
+
+    #[test]
+    fn uptime_ignores_repeated_and_past_hours() {
+        let mut uptime = uptime_with([1_000]);
+        assert!(!uptime.record(1_000));
+        assert!(!uptime.record(999));
+        assert_eq!(uptime.last_heartbeat_hour, 1_000);
+        assert_eq!(uptime.online_hours(990, 1_010), 1);
+    }
+
+    #[test]
+    fn uptime_clears_skipped_hours_in_ring() {
+        // 1720..1724 与 1000..1004 共用环形缓冲槽位, 跳过时必须清除旧记录
+        let mut uptime = uptime_with(1_000..1_010);
+        assert!(uptime.record(1_725));
+        assert_eq!(uptime.online_hours(1_720, 1_725), 0);
+        assert!(uptime.is_online(1_725));
+        // 窗口内仍保留 1006..1009 的历史心跳
+        assert_eq!(uptime.online_hours(1_006, 1_726), 5);
+        assert!(!uptime.is_online(1_005));
+    }
+
+    #[test]
+    fn uptime_gap_longer_than_window_resets_history() {
+        let mut uptime = uptime_with(1_000..1_010);
+        let hour = 1_009 + UPTIME_WINDOW_HOURS * 3;
+        assert!(uptime.record(hour));
+        assert_eq!(uptime.online_hours(0, hour + 1), 1);
+        assert_eq!(uptime.heartbeats.iter().map(|byte| byte.count_ones()).sum::<u32>(), 1);
+    }
+
+    fn leaf(i: u8) -> [u8; 32] {
+        hashv(&[&[i]]).to_bytes()
+    }
+
+    /// 构造补齐到 2 的幂的 Merkle 树, 返回根和每个分片的路径
+    fn merkle_tree(leaves: &[[u8; 32]]) -> ([u8; 32], Vec<Vec<[u8; 32]>>) {
+        let mut level = leaves.to_vec();
+        level.resize(leaves.len().next_power_of_two(), [0u8; 32]);
+        let mut proofs = vec![Vec::new(); leaves.len()];
+        let mut positions: Vec<usize> = (0..leaves.len()).collect();
+        while level.len() > 1 {
+            for (proof, position) in proofs.iter_mut().zip(positions.iter_mut()) {
+                proof.push(level[*position ^ 1]);
+                *position >>= 1;
+            }
+            level = level
+                .chunks(2)
+                .map(|pair| hashv(&[&pair[0], &pair[1]]).to_bytes())
+                .collect();
+        }
+        (level[0], proofs)
+    }
+
+    fn content(merkle_root: [u8; 32], piece_count: u32) -> Content {
+        Content {
+            info_hash: [1u8; 32],
+            merkle_root,
+            piece_count,
+            registered_by: Pubkey::default(),
+            registered_at: 0,
+            size_bytes: 1,
+            active_seeders: 0,
+            download_count: 0,
+        }
+    }
+
+    #[test]
+    fn merkle_proof_verifies_every_piece_with_padding() {
+        for piece_count in [1u32, 2, 3, 5, 8] {
+            let leaves: Vec<_> = (0..piece_count as u8).map(leaf).collect();
+            let (root, proofs) = merkle_tree(&leaves);
+            let content = content(root, piece_count);
+            for (index, proof) in proofs.iter().enumerate() {
+                assert!(verify_merkle_proof(&content, index as u32, leaves[index], proof));
+            }
+        }
+    }
+
+    #[test]
+    fn merkle_proof_rejects_wrong_length() {
+        let leaves: Vec<_> = (0..5).map(leaf).collect();
+        let (root, proofs) = merkle_tree(&leaves);
+        let content = content(root, 5);
+
+        let mut long = proofs[0].clone();
+        long.push([0u8; 32]);
+        assert!(!verify_merkle_proof(&content, 0, leaves[0], &long));
+        assert!(!verify_merkle_proof(&content, 0, leaves[0], &proofs[0][..2]));
+    }
+
+    #[test]
+    fn merkle_proof_rejects_swapped_order_and_wrong_piece() {
+        let leaves: Vec<_> = (0..4).map(leaf).collect();
+        let (root, proofs) = merkle_tree(&leaves);
+        let content = content(root, 4);
+
+        // 同一路径用于相邻分片时左右顺序相反
+        assert!(!verify_merkle_proof(&content, 1, leaves[0], &proofs[0]));
+        assert!(!verify_merkle_proof(&content, 0, leaves[1], &proofs[0]));
+        assert!(!verify_merkle_proof(&content, 2, leaves[2], &proofs[1]));
+    }
+
+    #[test]
+    fn ed25519_accepts_matching_signer_and_message() {
+        let signer = Pubkey::new_unique();
+        let ix = ed25519_ix(&signer, b"receipt");
+        assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_ok());
+    }
+
+    #[test]
+    fn ed25519_rejects_other_program() {
+        let signer = Pubkey::new_unique();
+        let mut ix = ed25519_ix(&signer, b"receipt");
+        ix.program_id = Pubkey::new_unique();
+        assert_eq!(
+            check_ed25519_instruction(&ix, &signer, b"receipt").unwrap_err(),
+            ErrorCode::MissingSignatureInstruction.into()
+        );
+    }
+
+    #[test]
+    fn ed25519_rejects_wrong_signer_or_message() {
+        let signer = Pubkey::new_unique();
+        let ix = ed25519_ix(&signer, b"receipt");
+        assert_eq!(
+            check_ed25519_instruction(&ix, &Pubkey::new_unique(), b"receipt").unwrap_err(),
+            ErrorCode::InvalidSignatureInstruction.into()
+        );
+        assert_eq!(
+            check_ed25519_instruction(&ix, &signer, b"receipt2").unwrap_err(),
+            ErrorCode::InvalidSignatureInstruction.into()
+        );
+    }
+
+    #[test]
+    fn ed25519_rejects_multiple_signatures() {
+        let signer = Pubkey::new_unique();
+        let mut ix = ed25519_ix(&signer, b"receipt");
+        ix.data[0] = 2;
+        assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_err());
+    }
+
+    #[test]
+    fn ed25519_rejects_data_from_other_instructions() {
+        let signer = Pubkey::new_unique();
+        // 签名、公钥或消息指向其他指令时, 内联数据并未被验证
+        for at in [4, 8, 14] {
+            let mut ix = ed25519_ix(&signer, b"receipt");
+            set_u16(&mut ix, at, 0);
+            assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_err());
+        }
+    }
+
+    #[test]
+    fn ed25519_rejects_out_of_range_offsets() {
+        let signer = Pubkey::new_unique();
+
+        let mut ix = ed25519_ix(&signer, b"receipt");
+        set_u16(&mut ix, 6, u16::MAX - 8);
+        assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_err());
+
+        let mut ix = ed25519_ix(&signer, b"receipt");
+        set_u16(&mut ix, 12, 1_000);
+        assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_err());
+
+        let mut ix = ed25519_ix(&signer, b"receipt");
+        ix.data.truncate(10);
+        assert!(check_ed25519_instruction(&ix, &signer, b"receipt").is_err());
+    }
 }